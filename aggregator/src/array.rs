@@ -27,4 +27,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: Vec<Record>, _a: &OneKeyRegistryArgs) -> Record {
         return Record::from_vec(state);
     }
+
+    fn merge(state: &mut Vec<Record>, other: Vec<Record>, _a: &OneKeyRegistryArgs) {
+        state.extend(other);
+    }
+
+    fn save(state: Vec<Record>, _a: &OneKeyRegistryArgs) -> Record {
+        return Record::from_vec(state);
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> Vec<Record> {
+        return r.expect_array().clone();
+    }
 }
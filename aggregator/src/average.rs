@@ -30,4 +30,20 @@ impl AggregatorBe for ImplBe {
     fn finish(state: (f64, f64), _a: &OneKeyRegistryArgs) -> Record {
         return Record::from(state.1 / state.0);
     }
+
+    fn merge(state: &mut (f64, f64), other: (f64, f64), _a: &OneKeyRegistryArgs) {
+        state.0 += other.0;
+        state.1 += other.1;
+    }
+
+    fn save(state: (f64, f64), _a: &OneKeyRegistryArgs) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("count", Record::from(state.0));
+        r.set_path("sum", Record::from(state.1));
+        return r;
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> (f64, f64) {
+        return (r.get_path("count").coerce_f64(), r.get_path("sum").coerce_f64());
+    }
 }
@@ -33,4 +33,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: Vec<String>, a: &Args) -> Record {
         return Record::from(state.join(&a.delimiter));
     }
+
+    fn merge(state: &mut Vec<String>, other: Vec<String>, _a: &Args) {
+        state.extend(other);
+    }
+
+    fn save(state: Vec<String>, _a: &Args) -> Record {
+        return Record::from_vec(state.into_iter().map(Record::from).collect());
+    }
+
+    fn load(r: Record, _a: &Args) -> Vec<String> {
+        return r.expect_array().iter().map(|v| v.expect_string().to_string()).collect();
+    }
 }
@@ -0,0 +1,43 @@
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::covariance::Args;
+use super::covariance::CoMomentsState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = Args;
+    type State = CoMomentsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["corr", "pearson"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute Pearson correlation of pairs of values";
+    }
+
+    fn add(state: &mut CoMomentsState, a: &Args, r: Record) {
+        state.add(r.get_path(&a.x_key).coerce_f64(), r.get_path(&a.y_key).coerce_f64());
+    }
+
+    fn finish(state: CoMomentsState, _a: &Args) -> Record {
+        return Record::from(state.correlation());
+    }
+
+    fn merge(state: &mut CoMomentsState, other: CoMomentsState, _a: &Args) {
+        state.merge(other);
+    }
+
+    fn save(state: CoMomentsState, _a: &Args) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &Args) -> CoMomentsState {
+        return CoMomentsState::load(r);
+    }
+}
@@ -1,4 +1,5 @@
 use record::Record;
+use record::RecordTrait;
 use registry::args::ZeroRegistryArgs;
 use super::AggregatorBe;
 use super::AggregatorRegistrant;
@@ -26,4 +27,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: i64, _a: &ZeroRegistryArgs) -> Record {
         return Record::from(state);
     }
+
+    fn merge(state: &mut i64, other: i64, _a: &ZeroRegistryArgs) {
+        *state += other;
+    }
+
+    fn save(state: i64, _a: &ZeroRegistryArgs) -> Record {
+        return Record::from(state);
+    }
+
+    fn load(r: Record, _a: &ZeroRegistryArgs) -> i64 {
+        return r.coerce_num().map_right(|f| f as i64).join();
+    }
 }
@@ -29,4 +29,18 @@ impl AggregatorBe for ImplBe {
     fn finish(state: HashMap<Arc<str>, i64>, _a: &OneKeyRegistryArgs) -> Record {
         return Record::from_hash(state.into_iter().map(|(v, ct)| (v, Record::from(ct))).collect());
     }
+
+    fn merge(state: &mut HashMap<Arc<str>, i64>, other: HashMap<Arc<str>, i64>, _a: &OneKeyRegistryArgs) {
+        for (v, ct) in other {
+            *state.entry(v).or_insert(0) += ct;
+        }
+    }
+
+    fn save(state: HashMap<Arc<str>, i64>, a: &OneKeyRegistryArgs) -> Record {
+        return Self::finish(state, a);
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> HashMap<Arc<str>, i64> {
+        return r.expect_hash().iter().map(|(v, ct)| (v.clone(), ct.coerce_num().map_right(|f| f as i64).join())).collect();
+    }
 }
@@ -0,0 +1,125 @@
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+#[derive(RegistryArgs)]
+pub(crate) struct Args {
+    pub(crate) x_key: Arc<str>,
+    pub(crate) y_key: Arc<str>,
+}
+
+// Count, means, co-moment, and second moments of pairs, updated per pair
+// (Welford) and merged pairwise (Chan et al.).
+#[derive(Clone)]
+#[derive(Default)]
+pub(crate) struct CoMomentsState {
+    n: f64,
+    mean_x: f64,
+    mean_y: f64,
+    c: f64,
+    m2_x: f64,
+    m2_y: f64,
+}
+
+impl CoMomentsState {
+    pub(crate) fn add(&mut self, x: f64, y: f64) {
+        self.n += 1.0;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / self.n;
+        self.mean_y += dy / self.n;
+        self.c += dx * (y - self.mean_y);
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+    }
+
+    pub(crate) fn merge(&mut self, other: CoMomentsState) {
+        if other.n == 0.0 {
+            return;
+        }
+        if self.n == 0.0 {
+            *self = other;
+            return;
+        }
+        let (a, b) = (self.clone(), other);
+        let n = a.n + b.n;
+        let dx = b.mean_x - a.mean_x;
+        let dy = b.mean_y - a.mean_y;
+        let f = a.n * b.n / n;
+        self.n = n;
+        self.mean_x = a.mean_x + dx * b.n / n;
+        self.mean_y = a.mean_y + dy * b.n / n;
+        self.c = a.c + b.c + dx * dy * f;
+        self.m2_x = a.m2_x + b.m2_x + dx * dx * f;
+        self.m2_y = a.m2_y + b.m2_y + dy * dy * f;
+    }
+
+    pub(crate) fn save(self) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("count", Record::from(self.n));
+        r.set_path("mean_x", Record::from(self.mean_x));
+        r.set_path("mean_y", Record::from(self.mean_y));
+        r.set_path("c", Record::from(self.c));
+        r.set_path("m2_x", Record::from(self.m2_x));
+        r.set_path("m2_y", Record::from(self.m2_y));
+        return r;
+    }
+
+    pub(crate) fn load(r: Record) -> CoMomentsState {
+        return CoMomentsState {
+            n: r.get_path("count").coerce_f64(),
+            mean_x: r.get_path("mean_x").coerce_f64(),
+            mean_y: r.get_path("mean_y").coerce_f64(),
+            c: r.get_path("c").coerce_f64(),
+            m2_x: r.get_path("m2_x").coerce_f64(),
+            m2_y: r.get_path("m2_y").coerce_f64(),
+        };
+    }
+
+    pub(crate) fn covariance(&self, ddof: f64) -> f64 {
+        return self.c / (self.n - ddof);
+    }
+
+    pub(crate) fn correlation(&self) -> f64 {
+        return self.c / (self.m2_x * self.m2_y).sqrt();
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = Args;
+    type State = CoMomentsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["cov", "covariance"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute sample covariance (n - 1 denominator) of pairs of values";
+    }
+
+    fn add(state: &mut CoMomentsState, a: &Args, r: Record) {
+        state.add(r.get_path(&a.x_key).coerce_f64(), r.get_path(&a.y_key).coerce_f64());
+    }
+
+    fn finish(state: CoMomentsState, _a: &Args) -> Record {
+        return Record::from(state.covariance(1.0));
+    }
+
+    fn merge(state: &mut CoMomentsState, other: CoMomentsState, _a: &Args) {
+        state.merge(other);
+    }
+
+    fn save(state: CoMomentsState, _a: &Args) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &Args) -> CoMomentsState {
+        return CoMomentsState::load(r);
+    }
+}
@@ -0,0 +1,92 @@
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+#[derive(RegistryArgs)]
+pub(crate) struct TimeSeriesArgs {
+    pub(crate) value_key: Arc<str>,
+    pub(crate) time_key: Arc<str>,
+}
+
+// The earliest and latest (by timestamp, not arrival) points seen.
+#[derive(Clone)]
+#[derive(Default)]
+pub(crate) struct EndpointsState(Option<((f64, f64), (f64, f64))>);
+
+impl EndpointsState {
+    pub(crate) fn add(&mut self, t: f64, v: f64) {
+        self.add_ends((t, v), (t, v));
+    }
+
+    fn add_ends(&mut self, first: (f64, f64), last: (f64, f64)) {
+        self.0 = Some(match self.0 {
+            None => (first, last),
+            Some((first0, last0)) => {
+                (if first.0 < first0.0 { first } else { first0 }, if last.0 >= last0.0 { last } else { last0 })
+            }
+        });
+    }
+
+    pub(crate) fn delta(&self) -> (f64, f64) {
+        let ((t0, v0), (t1, v1)) = self.0.expect("no points");
+        return (t1 - t0, v1 - v0);
+    }
+
+    pub(crate) fn merge(&mut self, other: EndpointsState) {
+        if let Some((first, last)) = other.0 {
+            self.add_ends(first, last);
+        }
+    }
+
+    pub(crate) fn save(self) -> Record {
+        return Record::from_vec(self.0.into_iter().flat_map(|(first, last)| vec![first, last]).map(|(t, v)| Record::from_vec(vec![Record::from(t), Record::from(v)])).collect());
+    }
+
+    pub(crate) fn load(r: Record) -> EndpointsState {
+        let ps: Vec<_> = r.expect_array().iter().map(|p| (p.get_path("#0").coerce_f64(), p.get_path("#1").coerce_f64())).collect();
+        let mut state = EndpointsState::default();
+        if ps.len() == 2 {
+            state.add_ends(ps[0], ps[1]);
+        }
+        return state;
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = TimeSeriesArgs;
+    type State = EndpointsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["delta"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute the value at the latest timestamp minus the value at the earliest";
+    }
+
+    fn add(state: &mut EndpointsState, a: &TimeSeriesArgs, r: Record) {
+        state.add(r.get_path(&a.time_key).coerce_timestamp(), r.get_path(&a.value_key).coerce_f64());
+    }
+
+    fn finish(state: EndpointsState, _a: &TimeSeriesArgs) -> Record {
+        return Record::from(state.delta().1);
+    }
+
+    fn merge(state: &mut EndpointsState, other: EndpointsState, _a: &TimeSeriesArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: EndpointsState, _a: &TimeSeriesArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &TimeSeriesArgs) -> EndpointsState {
+        return EndpointsState::load(r);
+    }
+}
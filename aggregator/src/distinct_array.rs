@@ -27,6 +27,12 @@ impl<T: Clone + Eq + Hash> DistinctSet<T> {
             self.v.push(t);
         }
     }
+
+    pub fn merge(&mut self, other: DistinctSet<T>) {
+        for t in other.v {
+            self.add(t);
+        }
+    }
 }
 
 impl<T> IntoIterator for DistinctSet<T> {
@@ -61,4 +67,20 @@ impl AggregatorBe for ImplBe {
     fn finish(state: DistinctSet<Record>, _a: &OneKeyRegistryArgs) -> Record {
         return Record::from_vec(state.into_iter().collect());
     }
+
+    fn merge(state: &mut DistinctSet<Record>, other: DistinctSet<Record>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: DistinctSet<Record>, _a: &OneKeyRegistryArgs) -> Record {
+        return Record::from_vec(state.into_iter().collect());
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> DistinctSet<Record> {
+        let mut state = DistinctSet::default();
+        for v in r.expect_array() {
+            state.add(v.clone());
+        }
+        return state;
+    }
 }
@@ -35,4 +35,20 @@ impl AggregatorBe for ImplBe {
         let vs: Vec<_> = state.into_iter().collect();
         return Record::from(vs.join(&a.delimiter));
     }
+
+    fn merge(state: &mut DistinctSet<String>, other: DistinctSet<String>, _a: &Args) {
+        state.merge(other);
+    }
+
+    fn save(state: DistinctSet<String>, _a: &Args) -> Record {
+        return Record::from_vec(state.into_iter().map(Record::from).collect());
+    }
+
+    fn load(r: Record, _a: &Args) -> DistinctSet<String> {
+        let mut state = DistinctSet::default();
+        for v in r.expect_array() {
+            state.add(v.expect_string().to_string());
+        }
+        return state;
+    }
 }
@@ -1,4 +1,5 @@
 use record::Record;
+use record::RecordTrait;
 use registry::args::OneKeyRegistryArgs;
 use super::AggregatorBe;
 use super::AggregatorRegistrant;
@@ -27,4 +28,20 @@ impl AggregatorBe for ImplBe {
     fn finish(state: DistinctSet<Record>, _a: &OneKeyRegistryArgs) -> Record {
         return Record::from(state.into_iter().count() as i64);
     }
+
+    fn merge(state: &mut DistinctSet<Record>, other: DistinctSet<Record>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: DistinctSet<Record>, _a: &OneKeyRegistryArgs) -> Record {
+        return Record::from_vec(state.into_iter().collect());
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> DistinctSet<Record> {
+        let mut state = DistinctSet::default();
+        for v in r.expect_array() {
+            state.add(v.clone());
+        }
+        return state;
+    }
 }
@@ -0,0 +1,133 @@
+use executor::CodeArg;
+use executor::LocalStream;
+use record::Record;
+use record::RecordTrait;
+use registry::Registrant;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
+use super::AggregatorInbox;
+use super::BoxedAggregator;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+pub(crate) struct Args {
+    init: CodeArg,
+    add: CodeArg,
+    finish: CodeArg,
+    merge: Option<CodeArg>,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",init,add,finish[,merge]";
+    }
+
+    fn argct() -> usize {
+        return 3;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let merge = match args.len() {
+            3 => None,
+            4 => Some(CodeArg::parse(args[3]).map_err(|e| e.label("While parsing merge"))?),
+            _ => return ValidationError::message("Too many arguments for eval (escape commas in code as \\,)"),
+        };
+        return Result::Ok(Args {
+            init: CodeArg::parse(args[0]).map_err(|e| e.label("While parsing init"))?,
+            add: CodeArg::parse(args[1]).map_err(|e| e.label("While parsing add"))?,
+            finish: CodeArg::parse(args[2]).map_err(|e| e.label("While parsing finish"))?,
+            merge: merge,
+        });
+    }
+}
+
+pub(crate) struct Impl;
+
+impl Registrant<BoxedAggregator> for Impl {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["eval"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "aggregate with code: init, add, and merge update {{state}} (add sees the input as {{record}}, merge a saved partial state as {{other}}), finish returns the result (merge is only needed for --merge-partial; escape commas in code as \\,)";
+    }
+
+    fn init(a: Args) -> BoxedAggregator {
+        return Box::new(EvalInbox {
+            init: a.init.stream(false),
+            add: a.add.stream(false),
+            finish: a.finish.stream(true),
+            merge: a.merge.map(|c| c.stream(false)),
+            state: None,
+        });
+    }
+}
+
+// Each code snippet runs against a hash holding the state (and any other
+// inputs) with the resulting state read back out.  The state is only
+// initialized on first use so clones of an unused prototype start fresh.
+struct EvalInbox {
+    init: LocalStream,
+    add: LocalStream,
+    finish: LocalStream,
+    merge: Option<LocalStream>,
+    state: Option<Record>,
+}
+
+impl EvalInbox {
+    fn take_state(&mut self) -> Record {
+        if let Some(state) = self.state.take() {
+            return state;
+        }
+        let mut r = Record::empty_hash();
+        r.set_path("state", Record::null());
+        return self.init.call(r).get_path("state");
+    }
+}
+
+impl AggregatorInbox for EvalInbox {
+    fn add(&mut self, r: Record) {
+        let mut input = Record::empty_hash();
+        input.set_path("state", self.take_state());
+        input.set_path("record", r);
+        self.state = Some(self.add.call(input).get_path("state"));
+    }
+
+    fn finish(mut self: Box<Self>) -> Record {
+        let mut input = Record::empty_hash();
+        input.set_path("state", self.take_state());
+        return self.finish.call(input);
+    }
+
+    fn merge(&mut self, partial: Record) {
+        let mut input = Record::empty_hash();
+        input.set_path("state", self.take_state());
+        input.set_path("other", partial);
+        let merge = self.merge.as_ref().expect("eval cannot merge partial states without merge code");
+        self.state = Some(merge.call(input).get_path("state"));
+    }
+
+    fn mergeable(&self) -> bool {
+        return self.merge.is_some();
+    }
+
+    fn save(mut self: Box<Self>) -> Record {
+        return self.take_state();
+    }
+
+    fn box_clone(&self) -> BoxedAggregator {
+        return Box::new(EvalInbox {
+            init: self.init.clone(),
+            add: self.add.clone(),
+            finish: self.finish.clone(),
+            merge: self.merge.clone(),
+            state: self.state.clone(),
+        });
+    }
+}
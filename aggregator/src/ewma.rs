@@ -0,0 +1,98 @@
+use record::Record;
+use record::RecordTrait;
+use registry_args::DurationArg;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+#[derive(RegistryArgs)]
+pub(crate) struct Args {
+    half_life: DurationArg,
+    value_key: Arc<str>,
+    time_key: Arc<str>,
+}
+
+// Each value is weighted by 2^(-age / half life) where age is relative to the
+// latest timestamp, so input order doesn't matter.  Sums are kept relative to
+// the latest timestamp seen and rescaled as it advances.
+#[derive(Clone)]
+#[derive(Default)]
+pub(crate) struct EwmaState {
+    t: Option<f64>,
+    sum: f64,
+    weight: f64,
+}
+
+impl EwmaState {
+    fn add(&mut self, half_life: f64, t: f64, sum: f64, weight: f64) {
+        let t0 = match self.t {
+            Some(t0) => t0,
+            None => t,
+        };
+        let decay = |dt: f64| (-dt * std::f64::consts::LN_2 / half_life).exp();
+        if t > t0 {
+            let d = decay(t - t0);
+            self.sum = self.sum * d + sum;
+            self.weight = self.weight * d + weight;
+            self.t = Some(t);
+        }
+        else {
+            let d = decay(t0 - t);
+            self.sum += sum * d;
+            self.weight += weight * d;
+            self.t = Some(t0);
+        }
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = Args;
+    type State = EwmaState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["ewma"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute an exponentially weighted moving average as of the latest timestamp, with the given half life";
+    }
+
+    fn add(state: &mut EwmaState, a: &Args, r: Record) {
+        state.add(a.half_life.0, r.get_path(&a.time_key).coerce_timestamp(), r.get_path(&a.value_key).coerce_f64(), 1.0);
+    }
+
+    fn finish(state: EwmaState, _a: &Args) -> Record {
+        return Record::from(state.sum / state.weight);
+    }
+
+    fn merge(state: &mut EwmaState, other: EwmaState, a: &Args) {
+        if let Some(t) = other.t {
+            state.add(a.half_life.0, t, other.sum, other.weight);
+        }
+    }
+
+    fn save(state: EwmaState, _a: &Args) -> Record {
+        let mut r = Record::empty_hash();
+        if let Some(t) = state.t {
+            r.set_path("t", Record::from(t));
+            r.set_path("sum", Record::from(state.sum));
+            r.set_path("weight", Record::from(state.weight));
+        }
+        return r;
+    }
+
+    fn load(r: Record, _a: &Args) -> EwmaState {
+        if !r.has_path("t") {
+            return EwmaState::default();
+        }
+        return EwmaState {
+            t: Some(r.get_path("t").coerce_f64()),
+            sum: r.get_path("sum").coerce_f64(),
+            weight: r.get_path("weight").coerce_f64(),
+        };
+    }
+}
@@ -0,0 +1,87 @@
+use executor::CodeArg;
+use executor::LocalStream;
+use record::Record;
+use registry::Registrant;
+use registry_args::RegistryArgs;
+use super::AggregatorInbox;
+use super::BoxedAggregator;
+use validates::ValidationResult;
+
+pub(crate) struct Args {
+    code: CodeArg,
+    inner: BoxedAggregator,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",code,aggregator...";
+    }
+
+    fn argct() -> usize {
+        return 2;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let (code, inner) = super::filter::parse_wrapper(args, true)?;
+        return Result::Ok(Args {
+            code: code,
+            inner: inner,
+        });
+    }
+}
+
+pub(crate) struct Impl;
+
+impl Registrant<BoxedAggregator> for Impl {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["expr"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "feed the value of code for each record to an inner aggregator (whose omitted keys default to that value; escape commas in code as \\,)";
+    }
+
+    fn init(a: Args) -> BoxedAggregator {
+        return Box::new(ExprInbox {
+            f: a.code.stream(true),
+            inner: a.inner,
+        });
+    }
+}
+
+struct ExprInbox {
+    f: LocalStream,
+    inner: BoxedAggregator,
+}
+
+impl AggregatorInbox for ExprInbox {
+    fn add(&mut self, r: Record) {
+        let v = self.f.call(r);
+        self.inner.add(v);
+    }
+
+    fn finish(self: Box<Self>) -> Record {
+        return self.inner.finish();
+    }
+
+    fn merge(&mut self, partial: Record) {
+        self.inner.merge(partial);
+    }
+
+    fn save(self: Box<Self>) -> Record {
+        return self.inner.save();
+    }
+
+    fn box_clone(&self) -> BoxedAggregator {
+        return Box::new(ExprInbox {
+            f: self.f.clone(),
+            inner: self.inner.clone(),
+        });
+    }
+}
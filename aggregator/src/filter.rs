@@ -0,0 +1,106 @@
+use executor::CodeArg;
+use executor::LocalStream;
+use record::Record;
+use record::RecordTrait;
+use registry::Registrant;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
+use super::AggregatorInbox;
+use super::BoxedAggregator;
+use validates::ValidationResult;
+
+// The code arg followed by an aggregator and its args.  If padded, the aggregator's missing trailing
+// args are filled in as empty (i.e. whole record) keys.
+pub(crate) fn parse_wrapper(args: &[&str], pad: bool) -> ValidationResult<(CodeArg, BoxedAggregator)> {
+    let code = CodeArg::parse(args[0]).map_err(|e| e.label("While parsing code"))?;
+    let name = args[1];
+    let mut inner_args = args[2..].to_vec();
+    if pad {
+        let argct = super::REGISTRY.argct(name)?;
+        while inner_args.len() < argct {
+            inner_args.push("");
+        }
+    }
+    let inner = super::REGISTRY.find(name, &inner_args).map_err(|e| e.label("While parsing aggregator"))?;
+    return Result::Ok((code, inner));
+}
+
+pub(crate) struct Args {
+    code: CodeArg,
+    inner: BoxedAggregator,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",code,aggregator...";
+    }
+
+    fn argct() -> usize {
+        return 2;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let (code, inner) = parse_wrapper(args, false)?;
+        return Result::Ok(Args {
+            code: code,
+            inner: inner,
+        });
+    }
+}
+
+pub(crate) struct Impl;
+
+impl Registrant<BoxedAggregator> for Impl {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["where", "filter"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "feed only records for which code is truthy to an inner aggregator (escape commas in code as \\,)";
+    }
+
+    fn init(a: Args) -> BoxedAggregator {
+        return Box::new(FilterInbox {
+            f: a.code.stream(true),
+            inner: a.inner,
+        });
+    }
+}
+
+struct FilterInbox {
+    f: LocalStream,
+    inner: BoxedAggregator,
+}
+
+impl AggregatorInbox for FilterInbox {
+    fn add(&mut self, r: Record) {
+        if self.f.call(r.clone()).coerce_bool() {
+            self.inner.add(r);
+        }
+    }
+
+    fn finish(self: Box<Self>) -> Record {
+        return self.inner.finish();
+    }
+
+    fn merge(&mut self, partial: Record) {
+        self.inner.merge(partial);
+    }
+
+    fn save(self: Box<Self>) -> Record {
+        return self.inner.save();
+    }
+
+    fn box_clone(&self) -> BoxedAggregator {
+        return Box::new(FilterInbox {
+            f: self.f.clone(),
+            inner: self.inner.clone(),
+        });
+    }
+}
@@ -0,0 +1,45 @@
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::top_k::NKeyArgs;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = NKeyArgs;
+    type State = Vec<Record>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["firstn"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "collect the first n values into an array";
+    }
+
+    fn add(state: &mut Vec<Record>, a: &NKeyArgs, r: Record) {
+        if state.len() < a.n {
+            state.push(r.get_path(&a.key));
+        }
+    }
+
+    fn finish(state: Vec<Record>, _a: &NKeyArgs) -> Record {
+        return Record::from_vec(state);
+    }
+
+    fn merge(state: &mut Vec<Record>, other: Vec<Record>, a: &NKeyArgs) {
+        let room = a.n - state.len();
+        state.extend(other.into_iter().take(room));
+    }
+
+    fn save(state: Vec<Record>, a: &NKeyArgs) -> Record {
+        return Self::finish(state, a);
+    }
+
+    fn load(r: Record, _a: &NKeyArgs) -> Vec<Record> {
+        return r.expect_array().clone();
+    }
+}
@@ -34,4 +34,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: BTreeMap<Arc<str>, Record>, _a: &Args) -> Record {
         return Record::from_hash(state);
     }
+
+    fn merge(state: &mut BTreeMap<Arc<str>, Record>, other: BTreeMap<Arc<str>, Record>, _a: &Args) {
+        state.extend(other);
+    }
+
+    fn save(state: BTreeMap<Arc<str>, Record>, _a: &Args) -> Record {
+        return Record::from_hash(state);
+    }
+
+    fn load(r: Record, _a: &Args) -> BTreeMap<Arc<str>, Record> {
+        return r.expect_hash().clone();
+    }
 }
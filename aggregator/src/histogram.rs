@@ -0,0 +1,190 @@
+use record::Record;
+use record::RecordTrait;
+use registry_args::RegistryArg;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+#[derive(Clone)]
+pub(crate) enum BucketsArg {
+    Width(f64),
+    Edges(Vec<f64>),
+    Log(f64),
+}
+
+impl RegistryArg for BucketsArg {
+    fn parse(arg: &str) -> ValidationResult<BucketsArg> {
+        let parts: Vec<_> = arg.split(':').collect();
+        match parts[0] {
+            "width" | "w" if parts.len() == 2 => {
+                let w = parts[1].parse::<f64>()?;
+                if !(w > 0.0) {
+                    return ValidationError::message(format!("Histogram width must be positive: {}", w));
+                }
+                return Result::Ok(BucketsArg::Width(w));
+            }
+            "edges" | "e" if parts.len() >= 3 => {
+                let mut edges = Vec::new();
+                for part in &parts[1..] {
+                    let e = part.parse::<f64>()?;
+                    if let Some(&prev) = edges.last() {
+                        if !(e > prev) {
+                            return ValidationError::message(format!("Histogram edges must be increasing: {}", arg));
+                        }
+                    }
+                    edges.push(e);
+                }
+                return Result::Ok(BucketsArg::Edges(edges));
+            }
+            "log" | "l" if parts.len() == 2 => {
+                let base = parts[1].parse::<f64>()?;
+                if !(base > 1.0) {
+                    return ValidationError::message(format!("Histogram log base must be greater than 1: {}", base));
+                }
+                return Result::Ok(BucketsArg::Log(base));
+            }
+            _ => {
+                return ValidationError::message(format!("Invalid histogram buckets (want width:W, edges:E1:E2:..., or log:BASE): {}", arg));
+            }
+        }
+    }
+}
+
+impl BucketsArg {
+    // Values below (above) the outermost edges, non-positive (infinite)
+    // values on a log scale, or too many widths out land in the i64::MIN
+    // (i64::MAX) bucket.
+    fn bucket(&self, v: f64) -> i64 {
+        match self {
+            BucketsArg::Width(w) => {
+                return (v / w).floor() as i64;
+            }
+            BucketsArg::Edges(edges) => {
+                if v < edges[0] {
+                    return i64::min_value();
+                }
+                if v >= edges[edges.len() - 1] {
+                    return i64::max_value();
+                }
+                return (edges.iter().take_while(|&&e| e <= v).count() - 1) as i64;
+            }
+            BucketsArg::Log(base) => {
+                if !(v > 0.0) {
+                    return i64::min_value();
+                }
+                if v.is_infinite() {
+                    return i64::max_value();
+                }
+                // The log can come out just under an exact power, so settle
+                // the exponent against the same powers the bounds use.
+                let mut e = v.log(*base).floor() as i64;
+                while base.powi((e + 1) as i32) <= v {
+                    e += 1;
+                }
+                while base.powi(e as i32) > v {
+                    e -= 1;
+                }
+                return e;
+            }
+        }
+    }
+
+    fn bounds(&self, b: i64) -> (Option<f64>, Option<f64>) {
+        match self {
+            BucketsArg::Width(w) => {
+                // Saturated buckets (values too far out for an i64 index)
+                // are open-ended.
+                if b == i64::min_value() {
+                    return (None, Some((b as f64 + 1.0) * w));
+                }
+                if b == i64::max_value() {
+                    return (Some(b as f64 * w), None);
+                }
+                return (Some(b as f64 * w), Some((b + 1) as f64 * w));
+            }
+            BucketsArg::Edges(edges) => {
+                if b == i64::min_value() {
+                    return (None, Some(edges[0]));
+                }
+                if b == i64::max_value() {
+                    return (Some(edges[edges.len() - 1]), None);
+                }
+                return (Some(edges[b as usize]), Some(edges[b as usize + 1]));
+            }
+            BucketsArg::Log(base) => {
+                if b == i64::min_value() {
+                    return (None, Some(0.0));
+                }
+                if b == i64::max_value() {
+                    return (None, None);
+                }
+                return (Some(base.powi(b as i32)), Some(base.powi(b as i32 + 1)));
+            }
+        }
+    }
+}
+
+#[derive(RegistryArgs)]
+pub(crate) struct Args {
+    buckets: BucketsArg,
+    key: Arc<str>,
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = Args;
+    type State = BTreeMap<i64, i64>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["histogram", "hist"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "count values into buckets (width:W, edges:E1:E2:..., or log:BASE) as an array of {lo, hi, count}";
+    }
+
+    fn add(state: &mut BTreeMap<i64, i64>, a: &Args, r: Record) {
+        *state.entry(a.buckets.bucket(r.get_path(&a.key).coerce_f64())).or_insert(0) += 1;
+    }
+
+    fn finish(mut state: BTreeMap<i64, i64>, a: &Args) -> Record {
+        // Explicit edges always report every inner bucket, even if empty.
+        if let BucketsArg::Edges(edges) = &a.buckets {
+            for b in 0..(edges.len() - 1) {
+                state.entry(b as i64).or_insert(0);
+            }
+        }
+        return Record::from_vec(state.into_iter().map(|(b, ct)| {
+            let (lo, hi) = a.buckets.bounds(b);
+            let mut r = Record::empty_hash();
+            if let Some(lo) = lo {
+                r.set_path("lo", Record::from(lo));
+            }
+            if let Some(hi) = hi {
+                r.set_path("hi", Record::from(hi));
+            }
+            r.set_path("count", Record::from(ct));
+            return r;
+        }).collect());
+    }
+
+    fn merge(state: &mut BTreeMap<i64, i64>, other: BTreeMap<i64, i64>, _a: &Args) {
+        for (b, ct) in other {
+            *state.entry(b).or_insert(0) += ct;
+        }
+    }
+
+    fn save(state: BTreeMap<i64, i64>, _a: &Args) -> Record {
+        return Record::from_hash(state.into_iter().map(|(b, ct)| (Arc::from(b.to_string()), Record::from(ct))).collect());
+    }
+
+    fn load(r: Record, _a: &Args) -> BTreeMap<i64, i64> {
+        return r.expect_hash().iter().map(|(b, ct)| (b.parse().unwrap(), ct.coerce_num().map_right(|f| f as i64).join())).collect();
+    }
+}
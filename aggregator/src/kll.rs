@@ -0,0 +1,131 @@
+use misc::seed::SeededRng;
+use rand::Rng;
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_percentile::PercentilesArgs;
+
+const K: f64 = 200.0;
+const C: f64 = 2.0 / 3.0;
+
+// A KLL sketch: level h holds values standing for 2^h inputs each, with lower
+// levels given geometrically less room.
+#[derive(Clone)]
+#[derive(Default)]
+pub(crate) struct Kll {
+    levels: Vec<Vec<f64>>,
+    // Created on first compaction so clones of an unused prototype don't
+    // share a stream.
+    rng: Option<SeededRng>,
+}
+
+impl Kll {
+    fn capacity(&self, h: usize) -> usize {
+        let depth = (self.levels.len() - 1 - h) as i32;
+        return std::cmp::max(2, (K * C.powi(depth)).ceil() as usize);
+    }
+
+    pub(crate) fn add(&mut self, v: f64) {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        self.levels[0].push(v);
+        self.compress();
+    }
+
+    pub(crate) fn merge(&mut self, other: Kll) {
+        for (h, level) in other.levels.into_iter().enumerate() {
+            if h == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+            self.levels[h].extend(level);
+        }
+        self.compress();
+    }
+
+    fn compress(&mut self) {
+        let mut h = 0;
+        while h < self.levels.len() {
+            if self.levels[h].len() >= self.capacity(h) {
+                if h + 1 == self.levels.len() {
+                    self.levels.push(Vec::new());
+                }
+                let mut level = std::mem::replace(&mut self.levels[h], Vec::new());
+                level.sort_by_key(|&v| F64SortDishonorProxy(v));
+                if level.len() % 2 == 1 {
+                    self.levels[h].push(level.pop().unwrap());
+                }
+                let rng = self.rng.get_or_insert_with(misc::seed::rng);
+                let offset = if rng.gen() { 1 } else { 0 };
+                let promoted: Vec<_> = level.into_iter().skip(offset).step_by(2).collect();
+                self.levels[h + 1].extend(promoted);
+            }
+            h += 1;
+        }
+    }
+
+    pub(crate) fn quantile(&self, q: f64) -> Record {
+        let mut items: Vec<(f64, f64)> = Vec::new();
+        for (h, level) in self.levels.iter().enumerate() {
+            let w = (1u64 << h) as f64;
+            items.extend(level.iter().map(|&v| (v, w)));
+        }
+        if items.is_empty() {
+            return Record::null();
+        }
+        items.sort_by_key(|&(v, _w)| F64SortDishonorProxy(v));
+
+        let total: f64 = items.iter().map(|(_v, w)| w).sum();
+        let target = q * total;
+        let mut so_far = 0.0;
+        for &(v, w) in items.iter() {
+            so_far += w;
+            if so_far > target {
+                return Record::from(v);
+            }
+        }
+        return Record::from(items[items.len() - 1].0);
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = PercentilesArgs;
+    type State = Kll;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["kll"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "estimate colon-separated percentiles of numeric values with a KLL sketch (k = 200: under a thousand values kept, rank error about 1.7%)";
+    }
+
+    fn add(state: &mut Kll, a: &PercentilesArgs, r: Record) {
+        state.add(r.get_path(&a.key).coerce_f64());
+    }
+
+    fn finish(state: Kll, a: &PercentilesArgs) -> Record {
+        return a.percentiles.finish(|p| state.quantile(p));
+    }
+
+    fn merge(state: &mut Kll, other: Kll, _a: &PercentilesArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: Kll, _a: &PercentilesArgs) -> Record {
+        return Record::from_vec(state.levels.into_iter().map(|level| Record::from_vec(level.into_iter().map(Record::from).collect())).collect());
+    }
+
+    fn load(r: Record, _a: &PercentilesArgs) -> Kll {
+        return Kll {
+            levels: r.expect_array().iter().map(|level| level.expect_array().iter().map(|v| v.coerce_f64()).collect()).collect(),
+            rng: None,
+        };
+    }
+}
@@ -0,0 +1,49 @@
+use record::Record;
+use record::RecordTrait;
+use std::collections::VecDeque;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::top_k::NKeyArgs;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = NKeyArgs;
+    type State = VecDeque<Record>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["lastn"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "collect the last n values into an array";
+    }
+
+    fn add(state: &mut VecDeque<Record>, a: &NKeyArgs, r: Record) {
+        state.push_back(r.get_path(&a.key));
+        if state.len() > a.n {
+            state.pop_front();
+        }
+    }
+
+    fn finish(state: VecDeque<Record>, _a: &NKeyArgs) -> Record {
+        return Record::from_vec(state.into_iter().collect());
+    }
+
+    fn merge(state: &mut VecDeque<Record>, other: VecDeque<Record>, a: &NKeyArgs) {
+        state.extend(other);
+        while state.len() > a.n {
+            state.pop_front();
+        }
+    }
+
+    fn save(state: VecDeque<Record>, a: &NKeyArgs) -> Record {
+        return Self::finish(state, a);
+    }
+
+    fn load(r: Record, _a: &NKeyArgs) -> VecDeque<Record> {
+        return r.expect_array().iter().cloned().collect();
+    }
+}
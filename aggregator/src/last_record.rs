@@ -1,4 +1,5 @@
 use record::Record;
+use record::RecordTrait;
 use registry::args::ZeroRegistryArgs;
 use super::AggregatorBe;
 use super::AggregatorRegistrant;
@@ -26,4 +27,18 @@ impl AggregatorBe for ImplBe {
     fn finish(state: Option<Record>, _a: &ZeroRegistryArgs) -> Record {
         return state.unwrap();
     }
+
+    fn merge(state: &mut Option<Record>, other: Option<Record>, _a: &ZeroRegistryArgs) {
+        if other.is_some() {
+            *state = other;
+        }
+    }
+
+    fn save(state: Option<Record>, _a: &ZeroRegistryArgs) -> Record {
+        return Record::from_vec(state.into_iter().collect());
+    }
+
+    fn load(r: Record, _a: &ZeroRegistryArgs) -> Option<Record> {
+        return r.expect_array().first().cloned();
+    }
 }
@@ -28,6 +28,20 @@ impl<K: Ord> MaxState<K> {
     pub fn finish(self) -> Record {
         return self.0.unwrap().1;
     }
+
+    pub fn merge(&mut self, other: MaxState<K>) {
+        if let Some((k, v)) = other.0 {
+            self.add(k, v);
+        }
+    }
+
+    pub fn save(self) -> Record {
+        return Record::from_vec(self.0.into_iter().map(|(_k, v)| v).collect());
+    }
+
+    pub fn load<F: Fn(&Record) -> K>(r: Record, f: F) -> Self {
+        return MaxState(r.expect_array().first().map(|v| (f(v), v.clone())));
+    }
 }
 
 pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
@@ -54,4 +68,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: MaxState<Arc<str>>, _a: &OneKeyRegistryArgs) -> Record {
         return state.finish();
     }
+
+    fn merge(state: &mut MaxState<Arc<str>>, other: MaxState<Arc<str>>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MaxState<Arc<str>>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> MaxState<Arc<str>> {
+        return MaxState::load(r, |v| v.expect_string());
+    }
 }
@@ -49,4 +49,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: MaxState<ReverseOrd<Arc<str>>>, _a: &OneKeyRegistryArgs) -> Record {
         return state.finish();
     }
+
+    fn merge(state: &mut MaxState<ReverseOrd<Arc<str>>>, other: MaxState<ReverseOrd<Arc<str>>>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MaxState<ReverseOrd<Arc<str>>>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> MaxState<ReverseOrd<Arc<str>>> {
+        return MaxState::load(r, |v| ReverseOrd(v.expect_string()));
+    }
 }
@@ -9,7 +9,7 @@ use validates::ValidationError;
 use validates::ValidationResult;
 
 #[derive(Clone)]
-pub(crate) struct PercentileArg(f64);
+pub(crate) struct PercentileArg(pub(crate) f64);
 
 impl Copy for PercentileArg {
 }
@@ -25,6 +25,32 @@ impl RegistryArg for PercentileArg {
     }
 }
 
+#[derive(Clone)]
+pub(crate) struct PercentilesArg(Vec<(Arc<str>, f64)>);
+
+impl RegistryArg for PercentilesArg {
+    fn parse(arg: &str) -> ValidationResult<PercentilesArg> {
+        let mut ps = Vec::new();
+        for a in arg.split(':') {
+            ps.push((Arc::from(a), PercentileArg::parse(a)?.0));
+        }
+        return Result::Ok(PercentilesArg(ps));
+    }
+}
+
+impl PercentilesArg {
+    // A hash from each percentile as written to f of its proportion.
+    pub(crate) fn finish<F: FnMut(f64) -> Record>(&self, mut f: F) -> Record {
+        return Record::from_hash(self.0.iter().map(|(label, prop)| (label.clone(), f(*prop))).collect());
+    }
+}
+
+#[derive(RegistryArgs)]
+pub(crate) struct PercentilesArgs {
+    pub(crate) percentiles: PercentilesArg,
+    pub(crate) key: Arc<str>,
+}
+
 #[derive(RegistryArgs)]
 pub(crate) struct PercentileArgs {
     pub(crate) percentile: PercentileArg,
@@ -45,14 +71,52 @@ impl<K: Ord> PercentileState<K> {
         self.0.push((k, v));
     }
 
-    pub fn finish(mut self, percentile: PercentileArg) -> Record {
+    fn sort(&mut self) {
         self.0.sort_by(|(k1, _v1), (k2, _v2)| k1.cmp(k2));
-        let mut idx = ((self.0.len() as f64) * percentile.0) as usize;
+    }
+
+    fn at(&self, prop: f64) -> Record {
+        let mut idx = ((self.0.len() as f64) * prop) as usize;
         if idx >= self.0.len() {
             idx = self.0.len() - 1;
         }
         return self.0[idx].1.clone();
     }
+
+    pub fn finish(mut self, percentile: PercentileArg) -> Record {
+        self.sort();
+        return self.at(percentile.0);
+    }
+
+    pub fn finish_many(mut self, percentiles: &PercentilesArg) -> Record {
+        self.sort();
+        return percentiles.finish(|prop| self.at(prop));
+    }
+
+    // The middle value, the mean of the two middle values, or null if empty.
+    pub fn median<F: Fn(&Record) -> f64>(mut self, f: F) -> Record {
+        self.sort();
+        let n = self.0.len();
+        if n == 0 {
+            return Record::null();
+        }
+        if n % 2 == 1 {
+            return self.0[n / 2].1.clone();
+        }
+        return Record::from((f(&self.0[n / 2 - 1].1) + f(&self.0[n / 2].1)) / 2.0);
+    }
+
+    pub fn merge(&mut self, other: PercentileState<K>) {
+        self.0.extend(other.0);
+    }
+
+    pub fn save(self) -> Record {
+        return Record::from_vec(self.0.into_iter().map(|(_k, v)| v).collect());
+    }
+
+    pub fn load<F: Fn(&Record) -> K>(r: Record, f: F) -> Self {
+        return PercentileState(r.expect_array().iter().map(|v| (f(v), v.clone())).collect());
+    }
 }
 
 pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
@@ -79,4 +143,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: PercentileState<Arc<str>>, a: &PercentileArgs) -> Record {
         return state.finish(a.percentile);
     }
+
+    fn merge(state: &mut PercentileState<Arc<str>>, other: PercentileState<Arc<str>>, _a: &PercentileArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: PercentileState<Arc<str>>, _a: &PercentileArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &PercentileArgs) -> PercentileState<Arc<str>> {
+        return PercentileState::load(r, |v| v.expect_string());
+    }
 }
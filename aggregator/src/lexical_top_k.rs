@@ -0,0 +1,45 @@
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::top_k::NKeyArgs;
+use super::top_k::TopKState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = NKeyArgs;
+    type State = TopKState<Arc<str>>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["ltopk"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "track the n lexically greatest values";
+    }
+
+    fn add(state: &mut TopKState<Arc<str>>, a: &NKeyArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.add(a.n, v.expect_string(), v);
+    }
+
+    fn finish(state: TopKState<Arc<str>>, _a: &NKeyArgs) -> Record {
+        return state.finish();
+    }
+
+    fn merge(state: &mut TopKState<Arc<str>>, other: TopKState<Arc<str>>, a: &NKeyArgs) {
+        state.merge(a.n, other);
+    }
+
+    fn save(state: TopKState<Arc<str>>, _a: &NKeyArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, a: &NKeyArgs) -> TopKState<Arc<str>> {
+        return TopKState::load(r, a.n, |v| v.expect_string());
+    }
+}
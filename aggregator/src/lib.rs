@@ -1,5 +1,6 @@
 #[macro_use]
 extern crate lazy_static;
+extern crate executor;
 extern crate misc;
 extern crate rand;
 extern crate record;
@@ -21,34 +22,70 @@ registry! {
     BoxedAggregator,
     array,
     average,
+    bottom_k,
     concat,
+    correlation,
     count,
     count_by,
+    covariance,
+    delta,
     distinct_array,
     distinct_concat,
     distinct_count,
+    eval,
+    ewma,
+    expr,
+    filter,
     first,
+    first_n,
     first_record,
     hash,
+    histogram,
+    hll,
+    kll,
+    kurtosis,
     last,
+    last_n,
     last_record,
+    lexical_bottom_k,
     lexical_max,
     lexical_min,
     lexical_percentile,
+    lexical_top_k,
     linear_regression,
     max,
+    median,
     min,
+    mode,
     percentile,
+    percentiles,
+    population_covariance,
+    population_variance,
+    rate,
+    record_for_bottom_k,
+    record_for_lexical_bottom_k,
     record_for_lexical_max,
     record_for_lexical_min,
     record_for_lexical_percentile,
+    record_for_lexical_top_k,
     record_for_max,
     record_for_min,
     record_for_percentile,
+    record_for_top_k,
     records,
     sample,
+    skewness,
+    spearman,
     standard_deviation,
     sum,
+    tdigest,
+    time_weighted_average,
+    top_k,
+    variance,
+    weighted_average,
+    weighted_percentile,
+    weighted_standard_deviation,
+    weighted_sum,
 }
 
 trait AggregatorBe {
@@ -59,11 +96,24 @@ trait AggregatorBe {
     fn help_msg() -> &'static str;
     fn add(state: &mut Self::State, a: &Self::Args, r: Record);
     fn finish(state: Self::State, a: &Self::Args) -> Record;
+
+    // Combine in a state built from later input.
+    fn merge(state: &mut Self::State, other: Self::State, a: &Self::Args);
+    // Partial states as records, so they can cross process boundaries.
+    fn save(state: Self::State, a: &Self::Args) -> Record;
+    fn load(r: Record, a: &Self::Args) -> Self::State;
 }
 
 pub trait AggregatorInbox: Send + Sync {
     fn add(&mut self, r: Record);
     fn finish(self: Box<Self>) -> Record;
+    // Combine in a partial (as returned by save()) from later input.
+    fn merge(&mut self, partial: Record);
+    // Whether merge() can be used at all.
+    fn mergeable(&self) -> bool {
+        return true;
+    }
+    fn save(self: Box<Self>) -> Record;
     fn box_clone(&self) -> BoxedAggregator;
 }
 
@@ -88,6 +138,16 @@ impl<B: AggregatorBe + 'static> AggregatorInbox for AggregatorInboxImpl<B> {
         return B::finish(self.s, &a);
     }
 
+    fn merge(&mut self, partial: Record) {
+        let other = B::load(partial, &self.a);
+        B::merge(&mut self.s, other, &self.a);
+    }
+
+    fn save(self: Box<Self>) -> Record {
+        let a = self.a.clone();
+        return B::save(self.s, &a);
+    }
+
     fn box_clone(&self) -> BoxedAggregator {
         return Box::new(AggregatorInboxImpl::<B> {
             a: self.a.clone(),
@@ -118,3 +178,6 @@ impl<B: AggregatorBe + 'static> Registrant<BoxedAggregator> for AggregatorRegist
         });
     }
 }
+
+#[cfg(test)]
+mod tests;
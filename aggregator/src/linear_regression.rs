@@ -58,13 +58,48 @@ impl AggregatorBe for ImplBe {
         let sbeta = (sbeta_numerator / sbeta_denominator).sqrt();
         let salpha = sbeta * (state.sx2 / state.s1).sqrt();
 
+        let r = (state.sxy * state.s1 - state.sx * state.sy) / ((state.sx2 * state.s1 - state.sx * state.sx) * (state.sy2 * state.s1 - state.sy * state.sy)).sqrt();
+
         let mut hash = BTreeMap::new();
 
         hash.insert(Arc::from("alpha"), Record::from(alpha));
         hash.insert(Arc::from("beta"), Record::from(beta));
         hash.insert(Arc::from("alpha_se"), Record::from(salpha));
         hash.insert(Arc::from("beta_se"), Record::from(sbeta));
+        hash.insert(Arc::from("r2"), Record::from(r * r));
+        hash.insert(Arc::from("residual_se"), Record::from(sbeta_numerator.sqrt()));
 
         return Record::from_hash(hash);
     }
+
+    fn merge(state: &mut State, other: State, _a: &Args) {
+        state.s1 += other.s1;
+        state.sx += other.sx;
+        state.sx2 += other.sx2;
+        state.sy += other.sy;
+        state.sy2 += other.sy2;
+        state.sxy += other.sxy;
+    }
+
+    fn save(state: State, _a: &Args) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("s1", Record::from(state.s1));
+        r.set_path("sx", Record::from(state.sx));
+        r.set_path("sx2", Record::from(state.sx2));
+        r.set_path("sy", Record::from(state.sy));
+        r.set_path("sy2", Record::from(state.sy2));
+        r.set_path("sxy", Record::from(state.sxy));
+        return r;
+    }
+
+    fn load(r: Record, _a: &Args) -> State {
+        return State {
+            s1: r.get_path("s1").coerce_f64(),
+            sx: r.get_path("sx").coerce_f64(),
+            sx2: r.get_path("sx2").coerce_f64(),
+            sy: r.get_path("sy").coerce_f64(),
+            sy2: r.get_path("sy2").coerce_f64(),
+            sxy: r.get_path("sxy").coerce_f64(),
+        };
+    }
 }
@@ -30,4 +30,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: MaxState<F64SortDishonorProxy>, _a: &OneKeyRegistryArgs) -> Record {
         return state.finish();
     }
+
+    fn merge(state: &mut MaxState<F64SortDishonorProxy>, other: MaxState<F64SortDishonorProxy>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MaxState<F64SortDishonorProxy>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> MaxState<F64SortDishonorProxy> {
+        return MaxState::load(r, |v| F64SortDishonorProxy(v.coerce_f64()));
+    }
 }
@@ -0,0 +1,45 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use registry::args::OneKeyRegistryArgs;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_percentile::PercentileState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = OneKeyRegistryArgs;
+    type State = PercentileState<F64SortDishonorProxy>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["median", "med"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute the median of values, averaging the middle two for even counts";
+    }
+
+    fn add(state: &mut PercentileState<F64SortDishonorProxy>, a: &OneKeyRegistryArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.add(F64SortDishonorProxy(v.coerce_f64()), v);
+    }
+
+    fn finish(state: PercentileState<F64SortDishonorProxy>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.median(|v| v.coerce_f64());
+    }
+
+    fn merge(state: &mut PercentileState<F64SortDishonorProxy>, other: PercentileState<F64SortDishonorProxy>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: PercentileState<F64SortDishonorProxy>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> PercentileState<F64SortDishonorProxy> {
+        return PercentileState::load(r, |v| F64SortDishonorProxy(v.coerce_f64()));
+    }
+}
@@ -31,4 +31,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: MaxState<ReverseOrd<F64SortDishonorProxy>>, _a: &OneKeyRegistryArgs) -> Record {
         return state.finish();
     }
+
+    fn merge(state: &mut MaxState<ReverseOrd<F64SortDishonorProxy>>, other: MaxState<ReverseOrd<F64SortDishonorProxy>>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MaxState<ReverseOrd<F64SortDishonorProxy>>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> MaxState<ReverseOrd<F64SortDishonorProxy>> {
+        return MaxState::load(r, |v| ReverseOrd(F64SortDishonorProxy(v.coerce_f64())));
+    }
 }
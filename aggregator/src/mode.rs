@@ -0,0 +1,63 @@
+use record::Record;
+use record::RecordTrait;
+use registry::args::OneKeyRegistryArgs;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+// Keyed by deparse so e.g. 1 and "1" are counted separately.
+pub(crate) type ModeState = BTreeMap<Arc<str>, (Record, i64)>;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = OneKeyRegistryArgs;
+    type State = ModeState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["mode"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "find the most common value (ties broken by first in lexical order)";
+    }
+
+    fn add(state: &mut ModeState, a: &OneKeyRegistryArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.entry(Arc::from(v.deparse())).or_insert((v, 0)).1 += 1;
+    }
+
+    fn finish(state: ModeState, _a: &OneKeyRegistryArgs) -> Record {
+        let mut best: Option<(Record, i64)> = None;
+        for (_k, (v, ct)) in state {
+            if let Some((_, best_ct)) = best {
+                if ct <= best_ct {
+                    continue;
+                }
+            }
+            best = Some((v, ct));
+        }
+        return best.map(|(v, _ct)| v).unwrap_or_else(Record::null);
+    }
+
+    fn merge(state: &mut ModeState, other: ModeState, _a: &OneKeyRegistryArgs) {
+        for (k, (v, ct)) in other {
+            state.entry(k).or_insert((v, 0)).1 += ct;
+        }
+    }
+
+    fn save(state: ModeState, _a: &OneKeyRegistryArgs) -> Record {
+        return Record::from_vec(state.into_iter().map(|(_k, (v, ct))| Record::from_vec(vec![v, Record::from(ct)])).collect());
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> ModeState {
+        return r.expect_array().iter().map(|p| {
+            let v = p.get_path("#0");
+            let ct = p.get_path("#1").coerce_num().map_right(|f| f as i64).join();
+            return (Arc::from(v.deparse()), (v, ct));
+        }).collect();
+    }
+}
@@ -30,4 +30,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: PercentileState<F64SortDishonorProxy>, a: &PercentileArgs) -> Record {
         return state.finish(a.percentile);
     }
+
+    fn merge(state: &mut PercentileState<F64SortDishonorProxy>, other: PercentileState<F64SortDishonorProxy>, _a: &PercentileArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: PercentileState<F64SortDishonorProxy>, _a: &PercentileArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &PercentileArgs) -> PercentileState<F64SortDishonorProxy> {
+        return PercentileState::load(r, |v| F64SortDishonorProxy(v.coerce_f64()));
+    }
 }
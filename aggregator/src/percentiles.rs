@@ -0,0 +1,45 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_percentile::PercentilesArgs;
+use super::lexical_percentile::PercentileState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = PercentilesArgs;
+    type State = PercentileState<F64SortDishonorProxy>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["percs"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute several percentiles (e.g. 50:90:99) of values sorted numerically";
+    }
+
+    fn add(state: &mut PercentileState<F64SortDishonorProxy>, a: &PercentilesArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.add(F64SortDishonorProxy(v.coerce_f64()), v);
+    }
+
+    fn finish(state: PercentileState<F64SortDishonorProxy>, a: &PercentilesArgs) -> Record {
+        return state.finish_many(&a.percentiles);
+    }
+
+    fn merge(state: &mut PercentileState<F64SortDishonorProxy>, other: PercentileState<F64SortDishonorProxy>, _a: &PercentilesArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: PercentileState<F64SortDishonorProxy>, _a: &PercentilesArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &PercentilesArgs) -> PercentileState<F64SortDishonorProxy> {
+        return PercentileState::load(r, |v| F64SortDishonorProxy(v.coerce_f64()));
+    }
+}
@@ -0,0 +1,47 @@
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::delta::EndpointsState;
+use super::delta::TimeSeriesArgs;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = TimeSeriesArgs;
+    type State = EndpointsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["rate"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute the change in value per second between the earliest and latest timestamps";
+    }
+
+    fn add(state: &mut EndpointsState, a: &TimeSeriesArgs, r: Record) {
+        state.add(r.get_path(&a.time_key).coerce_timestamp(), r.get_path(&a.value_key).coerce_f64());
+    }
+
+    fn finish(state: EndpointsState, _a: &TimeSeriesArgs) -> Record {
+        let (dt, dv) = state.delta();
+        if dt == 0.0 {
+            return Record::null();
+        }
+        return Record::from(dv / dt);
+    }
+
+    fn merge(state: &mut EndpointsState, other: EndpointsState, _a: &TimeSeriesArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: EndpointsState, _a: &TimeSeriesArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &TimeSeriesArgs) -> EndpointsState {
+        return EndpointsState::load(r);
+    }
+}
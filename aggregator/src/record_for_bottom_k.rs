@@ -0,0 +1,46 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_min::ReverseOrd;
+use super::top_k::NKeyArgs;
+use super::top_k::TopKState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = NKeyArgs;
+    type State = TopKState<ReverseOrd<F64SortDishonorProxy>>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["recforbottomk"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "track the records for the n numerically least values";
+    }
+
+    fn add(state: &mut TopKState<ReverseOrd<F64SortDishonorProxy>>, a: &NKeyArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.add(a.n, ReverseOrd(F64SortDishonorProxy(v.coerce_f64())), r);
+    }
+
+    fn finish(state: TopKState<ReverseOrd<F64SortDishonorProxy>>, _a: &NKeyArgs) -> Record {
+        return state.finish();
+    }
+
+    fn merge(state: &mut TopKState<ReverseOrd<F64SortDishonorProxy>>, other: TopKState<ReverseOrd<F64SortDishonorProxy>>, a: &NKeyArgs) {
+        state.merge(a.n, other);
+    }
+
+    fn save(state: TopKState<ReverseOrd<F64SortDishonorProxy>>, _a: &NKeyArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, a: &NKeyArgs) -> TopKState<ReverseOrd<F64SortDishonorProxy>> {
+        return TopKState::load(r, a.n, |r| ReverseOrd(F64SortDishonorProxy(r.get_path(&a.key).coerce_f64())));
+    }
+}
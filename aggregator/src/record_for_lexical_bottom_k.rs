@@ -0,0 +1,46 @@
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_min::ReverseOrd;
+use super::top_k::NKeyArgs;
+use super::top_k::TopKState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = NKeyArgs;
+    type State = TopKState<ReverseOrd<Arc<str>>>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["recforlbottomk"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "track the records for the n lexically least values";
+    }
+
+    fn add(state: &mut TopKState<ReverseOrd<Arc<str>>>, a: &NKeyArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.add(a.n, ReverseOrd(v.expect_string()), r);
+    }
+
+    fn finish(state: TopKState<ReverseOrd<Arc<str>>>, _a: &NKeyArgs) -> Record {
+        return state.finish();
+    }
+
+    fn merge(state: &mut TopKState<ReverseOrd<Arc<str>>>, other: TopKState<ReverseOrd<Arc<str>>>, a: &NKeyArgs) {
+        state.merge(a.n, other);
+    }
+
+    fn save(state: TopKState<ReverseOrd<Arc<str>>>, _a: &NKeyArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, a: &NKeyArgs) -> TopKState<ReverseOrd<Arc<str>>> {
+        return TopKState::load(r, a.n, |r| ReverseOrd(r.get_path(&a.key).expect_string()));
+    }
+}
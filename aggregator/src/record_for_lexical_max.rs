@@ -30,4 +30,19 @@ impl AggregatorBe for ImplBe {
     fn finish(state: MaxState<Arc<str>>, _a: &OneKeyRegistryArgs) -> Record {
         return state.finish();
     }
+
+    fn merge(state: &mut MaxState<Arc<str>>, other: MaxState<Arc<str>>, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MaxState<Arc<str>>, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, a: &OneKeyRegistryArgs) -> MaxState<Arc<str>> {
+        return MaxState::load(r, |r| {
+            let v = r.get_path(&a.key);
+            return v.expect_string();
+        });
+    }
 }
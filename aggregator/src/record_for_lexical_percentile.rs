@@ -30,4 +30,19 @@ impl AggregatorBe for ImplBe {
     fn finish(state: PercentileState<Arc<str>>, a: &PercentileArgs) -> Record {
         return state.finish(a.percentile);
     }
+
+    fn merge(state: &mut PercentileState<Arc<str>>, other: PercentileState<Arc<str>>, _a: &PercentileArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: PercentileState<Arc<str>>, _a: &PercentileArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, a: &PercentileArgs) -> PercentileState<Arc<str>> {
+        return PercentileState::load(r, |r| {
+            let v = r.get_path(&a.key);
+            return v.expect_string();
+        });
+    }
 }
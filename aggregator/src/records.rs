@@ -27,4 +27,16 @@ impl AggregatorBe for ImplBe {
     fn finish(state: Vec<Record>, _a: &ZeroRegistryArgs) -> Record {
         return Record::from_vec(state);
     }
+
+    fn merge(state: &mut Vec<Record>, other: Vec<Record>, _a: &ZeroRegistryArgs) {
+        state.extend(other);
+    }
+
+    fn save(state: Vec<Record>, _a: &ZeroRegistryArgs) -> Record {
+        return Record::from_vec(state);
+    }
+
+    fn load(r: Record, _a: &ZeroRegistryArgs) -> Vec<Record> {
+        return r.expect_array().clone();
+    }
 }
@@ -1,15 +1,46 @@
-use rand::Rng;
+use misc::seed::SeedSource;
 use record::Record;
 use record::RecordTrait;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
 use std::cmp::Ord;
 use std::sync::Arc;
 use super::AggregatorBe;
 use super::AggregatorRegistrant;
+use validates::ValidationError;
+use validates::ValidationResult;
 
-#[derive(RegistryArgs)]
 pub(crate) struct Args {
     count: usize,
     key: Arc<str>,
+    seed: SeedSource,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",count,key[,seed]";
+    }
+
+    fn argct() -> usize {
+        return 2;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let seed = match args.len() {
+            2 => None,
+            3 => Some(<u64 as RegistryArg>::parse(args[2]).map_err(|e| e.label("While parsing seed"))?),
+            _ => return ValidationError::message("Too many arguments for sample"),
+        };
+        return Result::Ok(Args {
+            count: <usize as RegistryArg>::parse(args[0]).map_err(|e| e.label("While parsing count"))?,
+            key: Arc::from(args[1]),
+            seed: SeedSource::new(seed),
+        });
+    }
 }
 
 pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
@@ -25,7 +56,7 @@ impl AggregatorBe for ImplBe {
     }
 
     fn help_msg() -> &'static str {
-        return "sample a specified number of values";
+        return "sample a specified number of values (optionally with a fixed seed)";
     }
 
     fn add(state: &mut (usize, Vec<(usize, Record)>), a: &Args, r: Record) {
@@ -37,7 +68,7 @@ impl AggregatorBe for ImplBe {
             state.1.push((idx, v));
             return;
         }
-        let pos = rand::thread_rng().gen_range(0, idx + 1);
+        let pos = a.seed.gen_range(0, idx + 1);
         if pos < a.count {
             state.1[pos] = (idx, v);
         }
@@ -47,4 +78,38 @@ impl AggregatorBe for ImplBe {
         state.1.sort_by(|(idx1, _v1), (idx2, _v2)| idx1.cmp(idx2));
         return Record::from_vec(state.1.into_iter().map(|(_idx, r)| r).collect());
     }
+
+    fn merge(state: &mut (usize, Vec<(usize, Record)>), other: (usize, Vec<(usize, Record)>), a: &Args) {
+        // Draw from each side in proportion to how much of the input it
+        // still stands for.
+        let (mut p1, mut s1) = (state.0, std::mem::replace(&mut state.1, Vec::new()));
+        let (mut p2, mut s2): (usize, Vec<_>) = (other.0, other.1.into_iter().map(|(idx, v)| (idx + state.0, v)).collect());
+        state.0 += other.0;
+        while state.1.len() < a.count && !(s1.is_empty() && s2.is_empty()) {
+            let from1 = !s1.is_empty() && (s2.is_empty() || a.seed.gen_range(0, p1 + p2) < p1);
+            if from1 {
+                let i = a.seed.gen_range(0, s1.len());
+                state.1.push(s1.swap_remove(i));
+                p1 -= 1;
+            }
+            else {
+                let i = a.seed.gen_range(0, s2.len());
+                state.1.push(s2.swap_remove(i));
+                p2 -= 1;
+            }
+        }
+    }
+
+    fn save(state: (usize, Vec<(usize, Record)>), _a: &Args) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("count", Record::from(state.0 as i64));
+        r.set_path("values", Record::from_vec(state.1.into_iter().map(|(idx, v)| Record::from_vec(vec![Record::from(idx as i64), v])).collect()));
+        return r;
+    }
+
+    fn load(r: Record, _a: &Args) -> (usize, Vec<(usize, Record)>) {
+        let count = r.get_path("count").coerce_num().map_right(|f| f as i64).join() as usize;
+        let values = r.get_path("values").expect_array().iter().map(|p| (p.get_path("#0").coerce_num().map_right(|f| f as i64).join() as usize, p.get_path("#1"))).collect();
+        return (count, values);
+    }
 }
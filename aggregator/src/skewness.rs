@@ -0,0 +1,43 @@
+use record::Record;
+use record::RecordTrait;
+use registry::args::OneKeyRegistryArgs;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::variance::MomentsState;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = OneKeyRegistryArgs;
+    type State = MomentsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["skew", "skewness"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute skewness (population, g1) of numeric values";
+    }
+
+    fn add(state: &mut MomentsState, a: &OneKeyRegistryArgs, r: Record) {
+        state.add(r.get_path(&a.key).coerce_f64());
+    }
+
+    fn finish(state: MomentsState, _a: &OneKeyRegistryArgs) -> Record {
+        return Record::from(state.skewness());
+    }
+
+    fn merge(state: &mut MomentsState, other: MomentsState, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MomentsState, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> MomentsState {
+        return MomentsState::load(r);
+    }
+}
@@ -0,0 +1,70 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::covariance::Args;
+use super::covariance::CoMomentsState;
+
+// Ranks (1-based, ties given their average rank) in input order.
+fn ranks(vs: &[f64]) -> Vec<f64> {
+    let mut idxs: Vec<usize> = (0..vs.len()).collect();
+    idxs.sort_by_key(|&i| F64SortDishonorProxy(vs[i]));
+    let mut ret = vec![0.0; vs.len()];
+    let mut start = 0;
+    while start < idxs.len() {
+        let mut end = start + 1;
+        while end < idxs.len() && vs[idxs[end]] == vs[idxs[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &idxs[start..end] {
+            ret[i] = rank;
+        }
+        start = end;
+    }
+    return ret;
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = Args;
+    type State = Vec<(f64, f64)>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["spearman"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute Spearman rank correlation of pairs of values";
+    }
+
+    fn add(state: &mut Vec<(f64, f64)>, a: &Args, r: Record) {
+        state.push((r.get_path(&a.x_key).coerce_f64(), r.get_path(&a.y_key).coerce_f64()));
+    }
+
+    fn finish(state: Vec<(f64, f64)>, _a: &Args) -> Record {
+        let xs: Vec<_> = state.iter().map(|&(x, _y)| x).collect();
+        let ys: Vec<_> = state.iter().map(|&(_x, y)| y).collect();
+        let mut cm = CoMomentsState::default();
+        for (x, y) in ranks(&xs).into_iter().zip(ranks(&ys).into_iter()) {
+            cm.add(x, y);
+        }
+        return Record::from(cm.correlation());
+    }
+
+    fn merge(state: &mut Vec<(f64, f64)>, other: Vec<(f64, f64)>, _a: &Args) {
+        state.extend(other);
+    }
+
+    fn save(state: Vec<(f64, f64)>, _a: &Args) -> Record {
+        return Record::from_vec(state.into_iter().map(|(x, y)| Record::from_vec(vec![Record::from(x), Record::from(y)])).collect());
+    }
+
+    fn load(r: Record, _a: &Args) -> Vec<(f64, f64)> {
+        return r.expect_array().iter().map(|p| (p.get_path("#0").coerce_f64(), p.get_path("#1").coerce_f64())).collect();
+    }
+}
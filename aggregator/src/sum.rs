@@ -12,6 +12,21 @@ pub(crate) struct ImplBe;
 #[derive(Clone)]
 pub(crate) struct State(Either<i64, f64>);
 
+impl State {
+    fn add(&mut self, n1: Either<i64, f64>) {
+        let n2 = self.0.clone();
+        if let Either::Left(i1) = n1 {
+            if let Either::Left(i2) = n2 {
+                *self = State(Either::Left(i1 + i2));
+                return;
+            }
+        }
+        let f1 = n1.map_left(|i| i as f64).join();
+        let f2 = n2.map_left(|i| i as f64).join();
+        *self = State(Either::Right(f1 + f2));
+    }
+}
+
 impl Default for State {
     fn default() -> Self {
         return State(Either::Left(0));
@@ -31,23 +46,22 @@ impl AggregatorBe for ImplBe {
     }
 
     fn add(state: &mut State, a: &OneKeyRegistryArgs, r: Record) {
-        let n1 = r.get_path(&a.key).coerce_num();
-        let n2 = state.0.clone();
+        state.add(r.get_path(&a.key).coerce_num());
+    }
 
-        if let Either::Left(i1) = n1 {
-            if let Either::Left(i2) = n2 {
-                *state = State(Either::Left(i1 + i2));
-                return;
-            }
-        }
+    fn finish(state: State, _a: &OneKeyRegistryArgs) -> Record {
+        return state.0.map_left(Record::from).map_right(Record::from).join();
+    }
 
-        let f1 = n1.map_left(|i| i as f64).join();
-        let f2 = n2.map_left(|i| i as f64).join();
+    fn merge(state: &mut State, other: State, _a: &OneKeyRegistryArgs) {
+        state.add(other.0);
+    }
 
-        *state = State(Either::Right(f1 + f2));
+    fn save(state: State, a: &OneKeyRegistryArgs) -> Record {
+        return Self::finish(state, a);
     }
 
-    fn finish(state: State, _a: &OneKeyRegistryArgs) -> Record {
-        return state.0.map_left(Record::from).map_right(Record::from).join();
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> State {
+        return State(r.coerce_num());
     }
 }
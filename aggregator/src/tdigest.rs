@@ -0,0 +1,165 @@
+use record::Record;
+use record::RecordTrait;
+use std::cmp::Ordering;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_percentile::PercentilesArgs;
+
+const COMPRESSION: f64 = 100.0;
+const BUFFER: usize = 500;
+
+// A merging t-digest: (mean, weight) centroids sorted by mean, with incoming
+// values buffered and folded in periodically.
+#[derive(Clone)]
+#[derive(Default)]
+pub(crate) struct TDigest {
+    centroids: Vec<(f64, f64)>,
+    buffer: Vec<f64>,
+    min: f64,
+    max: f64,
+}
+
+fn scale(q: f64) -> f64 {
+    return COMPRESSION / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).asin();
+}
+
+impl TDigest {
+    pub(crate) fn add(&mut self, v: f64) {
+        if self.centroids.is_empty() && self.buffer.is_empty() {
+            self.min = v;
+            self.max = v;
+        }
+        self.min = self.min.min(v);
+        self.max = self.max.max(v);
+        self.buffer.push(v);
+        if self.buffer.len() >= BUFFER {
+            self.compress();
+        }
+    }
+
+    pub(crate) fn merge(&mut self, mut other: TDigest) {
+        other.compress();
+        if other.centroids.is_empty() {
+            return;
+        }
+        if self.centroids.is_empty() && self.buffer.is_empty() {
+            *self = other;
+            return;
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.centroids.extend(other.centroids);
+        self.compress();
+    }
+
+    pub(crate) fn save(mut self) -> Record {
+        self.compress();
+        let mut r = Record::empty_hash();
+        r.set_path("centroids", Record::from_vec(self.centroids.iter().map(|&(m, w)| Record::from_vec(vec![Record::from(m), Record::from(w)])).collect()));
+        r.set_path("min", Record::from(self.min));
+        r.set_path("max", Record::from(self.max));
+        return r;
+    }
+
+    pub(crate) fn load(r: Record) -> TDigest {
+        return TDigest {
+            centroids: r.get_path("centroids").expect_array().iter().map(|c| (c.get_path("#0").coerce_f64(), c.get_path("#1").coerce_f64())).collect(),
+            buffer: Vec::new(),
+            min: r.get_path("min").coerce_f64(),
+            max: r.get_path("max").coerce_f64(),
+        };
+    }
+
+    fn compress(&mut self) {
+        let mut all: Vec<(f64, f64)> = self.centroids.drain(..).collect();
+        all.extend(self.buffer.drain(..).map(|v| (v, 1.0)));
+        if all.is_empty() {
+            return;
+        }
+        all.sort_by(|(m1, _w1), (m2, _w2)| m1.partial_cmp(m2).unwrap_or(Ordering::Equal));
+
+        let total: f64 = all.iter().map(|(_m, w)| w).sum();
+        let mut all = all.into_iter();
+        let mut cur = all.next().unwrap();
+        let mut so_far = 0.0;
+        for c in all {
+            if scale((so_far + cur.1 + c.1) / total) - scale(so_far / total) <= 1.0 {
+                cur.0 = (cur.0 * cur.1 + c.0 * c.1) / (cur.1 + c.1);
+                cur.1 += c.1;
+            }
+            else {
+                so_far += cur.1;
+                self.centroids.push(cur);
+                cur = c;
+            }
+        }
+        self.centroids.push(cur);
+    }
+
+    pub(crate) fn quantile(&mut self, q: f64) -> Record {
+        self.compress();
+        let cs = &self.centroids;
+        if cs.is_empty() {
+            return Record::null();
+        }
+        if cs.len() == 1 {
+            return Record::from(cs[0].0);
+        }
+
+        let total: f64 = cs.iter().map(|(_m, w)| w).sum();
+        let target = q * total;
+
+        // Interpolate between centroid centers, and between the extreme
+        // centroids and the true min/max.
+        let mut prev = (self.min, 0.0);
+        let mut so_far = 0.0;
+        for &(m, w) in cs.iter() {
+            let center = so_far + w / 2.0;
+            if target < center {
+                let t = if center > prev.1 { (target - prev.1) / (center - prev.1) } else { 0.0 };
+                return Record::from(prev.0 + t * (m - prev.0));
+            }
+            prev = (m, center);
+            so_far += w;
+        }
+        let t = if total > prev.1 { (target - prev.1) / (total - prev.1) } else { 0.0 };
+        return Record::from(prev.0 + t * (self.max - prev.0));
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = PercentilesArgs;
+    type State = TDigest;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["tdigest"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "estimate colon-separated percentiles of numeric values with a t-digest (compression 100: a few hundred centroids; rank error typically well under 1%, smallest near the extremes)";
+    }
+
+    fn add(state: &mut TDigest, a: &PercentilesArgs, r: Record) {
+        state.add(r.get_path(&a.key).coerce_f64());
+    }
+
+    fn finish(mut state: TDigest, a: &PercentilesArgs) -> Record {
+        return a.percentiles.finish(|p| state.quantile(p));
+    }
+
+    fn merge(state: &mut TDigest, other: TDigest, _a: &PercentilesArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: TDigest, _a: &PercentilesArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &PercentilesArgs) -> TDigest {
+        return TDigest::load(r);
+    }
+}
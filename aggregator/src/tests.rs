@@ -0,0 +1,406 @@
+use record::Record;
+use record::RecordTrait;
+use super::BoxedAggregator;
+
+fn agg(spec: &str) -> BoxedAggregator {
+    let parts = registry::split_args(spec);
+    let args: Vec<_> = parts[1..].iter().map(|s| s as &str).collect();
+    return super::REGISTRY.find(&parts[0], &args).unwrap_or_else(|_| panic!("Bad aggregator: {}", spec));
+}
+
+fn records(json: &[&str]) -> Vec<Record> {
+    return json.iter().map(|s| Record::parse(s)).collect();
+}
+
+// Records {"x": v} for the given values.
+fn xs(vs: &[f64]) -> Vec<Record> {
+    return vs.iter().map(|&v| {
+        let mut r = Record::empty_hash();
+        r.set_path("x", Record::from(v));
+        return r;
+    }).collect();
+}
+
+fn finish(spec: &str, rs: &[Record]) -> Record {
+    let mut a = agg(spec);
+    for r in rs {
+        a.add(r.clone());
+    }
+    return a.finish();
+}
+
+// Aggregates the input in `parts` pieces, each saved and reloaded through
+// JSON, then merged in order.
+fn finish_merged(spec: &str, rs: &[Record], parts: usize) -> Record {
+    let mut a = agg(spec);
+    for chunk in rs.chunks((rs.len() + parts - 1) / parts) {
+        let mut a1 = agg(spec);
+        for r in chunk {
+            a1.add(r.clone());
+        }
+        a.merge(Record::parse(&a1.save().deparse()));
+    }
+    return a.finish();
+}
+
+// 0 to n - 1, scrambled.
+fn scrambled(n: usize) -> Vec<f64> {
+    return (0..n).map(|i| ((i * 7919) % n) as f64).collect();
+}
+
+fn assert_ranks(r: &Record, n: usize, tolerance: f64) {
+    for (label, v) in r.expect_hash() {
+        let p: f64 = label.parse::<f64>().unwrap() / 100.0;
+        let rank = v.coerce_f64() / (n as f64);
+        assert!((rank - p).abs() <= tolerance, "percentile {} came out at rank {}", label, rank);
+    }
+}
+
+#[test]
+fn test_hll() {
+    let rs: Vec<_> = (0..20000).map(|i| Record::from((i % 10000) as i64)).map(|v| {
+        let mut r = Record::empty_hash();
+        r.set_path("x", v);
+        return r;
+    }).collect();
+    for &(p, tolerance) in &[(10, 0.15), (14, 0.04)] {
+        let spec = format!("hll,{},x", p);
+        let est = finish(&spec, &rs).coerce_f64();
+        assert!((est / 10000.0 - 1.0).abs() <= tolerance, "hll at precision {} estimated {}", p, est);
+        // Merging is exactly a register max, so pieces don't matter.
+        assert_eq!(finish_merged(&spec, &rs, 7).coerce_f64(), est);
+    }
+    assert_eq!(finish("hll,10,x", &records(&[r#"{"x":"a"}"#, r#"{"x":"b"}"#, r#"{"x":"a"}"#])).coerce_f64(), 2.0);
+    assert_eq!(finish("hll,10,x", &[]).coerce_f64(), 0.0);
+    assert!(super::REGISTRY.find("hll", &["3", "x"]).is_err());
+    assert!(super::REGISTRY.find("hll", &["19", "x"]).is_err());
+}
+
+#[test]
+fn test_tdigest() {
+    let n = 20000;
+    let rs = xs(&scrambled(n));
+    let spec = "tdigest,1:10:50:90:99:99.9,x";
+    assert_ranks(&finish(spec, &rs), n, 0.01);
+    assert_ranks(&finish_merged(spec, &rs, 9), n, 0.01);
+    // Extremes are exact.
+    let r = finish("tdigest,0:100,x", &rs);
+    assert_eq!(r.get_path("0").coerce_f64(), 0.0);
+    assert_eq!(r.get_path("100").coerce_f64(), (n - 1) as f64);
+    assert_eq!(finish("tdigest,50,x", &xs(&[3.0])).get_path("50").coerce_f64(), 3.0);
+}
+
+#[test]
+fn test_kll() {
+    let n = 20000;
+    let rs = xs(&scrambled(n));
+    let spec = "kll,1:10:50:90:99,x";
+    assert_ranks(&finish(spec, &rs), n, 0.05);
+    assert_ranks(&finish_merged(spec, &rs, 9), n, 0.05);
+    // Small inputs are kept whole.
+    let r = finish("kll,0:50:100,x", &xs(&[5.0, 1.0, 3.0]));
+    assert_eq!(r.deparse(), r#"{"0":1.0,"100":5.0,"50":3.0}"#);
+    assert!(super::REGISTRY.find("kll", &["101", "x"]).is_err());
+}
+
+fn xys() -> Vec<Record> {
+    return (0..50).map(|i| {
+        let mut r = Record::empty_hash();
+        r.set_path("x", Record::from(((i * 17) % 23) as i64));
+        r.set_path("y", Record::from(((i * 5) % 7) as f64 + 0.5 * (i as f64)));
+        r.set_path("s", Record::from(format!("s{}", i % 6)));
+        return r;
+    }).collect();
+}
+
+#[test]
+fn test_merge_exact() {
+    let rs = xys();
+    let specs = [
+        "array,x", "average,x", "concatenate,;,s", "count", "countby,s", "darray,s", "dconcatenate,;,s", "dcount,s",
+        "first,x", "firstrecord", "hash,s,x", "last,x", "lastrecord", "lmax,s", "lmin,s", "lperc,50,s", "max,x", "min,x",
+        "perc,90,x", "recformax,x", "recformin,x", "recforlperc,20,s", "recforperc,20,x", "records", "sum,x",
+    ];
+    for spec in &specs {
+        let whole = finish(spec, &rs);
+        for &parts in &[1, 3, 50] {
+            assert_eq!(finish_merged(spec, &rs, parts), whole, "{} in {} parts", spec, parts);
+        }
+    }
+    // Merging into a fresh state and merging an empty one are no-ops.
+    for spec in &specs {
+        let mut a = agg(spec);
+        a.merge(agg(spec).save());
+        for r in &rs {
+            a.add(r.clone());
+        }
+        a.merge(agg(spec).save());
+        assert_eq!(a.finish(), finish(spec, &rs), "{} with empty partials", spec);
+    }
+}
+
+#[test]
+fn test_merge_float() {
+    let rs = xys();
+    for spec in &["stddev,y", "linreg,x,y", "sum,y"] {
+        let whole = finish(spec, &rs);
+        let merged = finish_merged(spec, &rs, 4);
+        let (whole, merged) = (whole.deparse(), merged.deparse());
+        let nums = |s: &str| s.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == 'e')).filter_map(|p| p.parse::<f64>().ok()).collect::<Vec<_>>();
+        let (n1, n2) = (nums(&whole), nums(&merged));
+        assert_eq!(n1.len(), n2.len(), "{}: {} vs {}", spec, whole, merged);
+        for (v1, v2) in n1.iter().zip(n2.iter()) {
+            assert!((v1 - v2).abs() <= 1e-9 * v1.abs().max(1.0), "{}: {} vs {}", spec, whole, merged);
+        }
+    }
+}
+
+fn pairs(xs0: &[f64], ys0: &[f64]) -> Vec<Record> {
+    return xs0.iter().zip(ys0.iter()).map(|(&x, &y)| {
+        let mut r = Record::empty_hash();
+        r.set_path("x", Record::from(x));
+        r.set_path("y", Record::from(y));
+        return r;
+    }).collect();
+}
+
+fn assert_close(spec: &str, rs: &[Record], expected: f64) {
+    assert_within(spec, rs, expected, 1e-9);
+}
+
+fn assert_within(spec: &str, rs: &[Record], expected: f64, tolerance: f64) {
+    for r in &[finish(spec, rs), finish_merged(spec, rs, 3)] {
+        let v = r.coerce_f64();
+        assert!((v - expected).abs() < tolerance, "{} came out {}, expected {}", spec, v, expected);
+    }
+}
+
+#[test]
+fn test_moments() {
+    let rs = pairs(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], &[1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0, 7.0]);
+    assert_close("pvar,x", &rs, 4.0);
+    assert_close("var,x", &rs, 32.0 / 7.0);
+    assert_close("skew,x", &rs, 0.65625);
+    assert_close("kurt,x", &rs, -0.21875);
+    assert_close("pcov,x,y", &rs, 3.875);
+    assert_close("cov,x,y", &rs, 31.0 / 7.0);
+    assert_close("corr,x,y", &rs, 0.8455943246644705);
+    // Ties share their average rank.
+    assert_close("spearman,x,y", &rs, 0.8838515090524254);
+    // Shifting by a large constant loses little more than the inputs' own
+    // precision (summing squares would leave nothing).
+    let shifted: Vec<_> = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().map(|v| v + 1e9).collect();
+    assert_within("pvar,x", &pairs(&shifted, &shifted), 4.0, 1e-6);
+    assert_within("pcov,x,y", &pairs(&shifted, &shifted), 4.0, 1e-6);
+}
+
+#[test]
+fn test_rank_correlation() {
+    let xs0: Vec<_> = (1..=8).map(|i| i as f64).collect();
+    let cubes: Vec<_> = xs0.iter().map(|x| x * x * x).collect();
+    let negs: Vec<_> = xs0.iter().map(|x| -x).collect();
+    assert_close("spearman,x,y", &pairs(&xs0, &cubes), 1.0);
+    assert_close("spearman,x,y", &pairs(&xs0, &negs), -1.0);
+    assert!(finish("corr,x,y", &pairs(&xs0, &cubes)).coerce_f64() < 1.0);
+}
+
+#[test]
+fn test_histogram() {
+    let rs = xs(&[0.5, 1.0, 2.5, 9.99, 10.0, -3.0]);
+    assert_eq!(finish("histogram,width:5,x", &rs).deparse(), r#"[{"count":1,"hi":0.0,"lo":-5.0},{"count":3,"hi":5.0,"lo":0.0},{"count":1,"hi":10.0,"lo":5.0},{"count":1,"hi":15.0,"lo":10.0}]"#);
+    assert_eq!(finish("histogram,edges:1:2:10,x", &rs).deparse(), r#"[{"count":2,"hi":1.0},{"count":1,"hi":2.0,"lo":1.0},{"count":2,"hi":10.0,"lo":2.0},{"count":1,"lo":10.0}]"#);
+    assert_eq!(finish_merged("histogram,edges:1:2:10,x", &rs, 4), finish("histogram,edges:1:2:10,x", &rs));
+    assert!(super::REGISTRY.find("histogram", &["edges:2:1", "x"]).is_err());
+    assert!(super::REGISTRY.find("histogram", &["log:1", "x"]).is_err());
+}
+
+#[test]
+fn test_histogram_width_saturated() {
+    // Too many widths out for an index: open-ended buckets at either end.
+    let r = finish("histogram,width:1,x", &xs(&[1e300, -1e300, 0.5]));
+    assert_eq!(r.expect_array().len(), 3);
+    assert_eq!(r.get_path("#0/lo").deparse(), "null");
+    assert_eq!(r.get_path("#0/count").coerce_f64(), 1.0);
+    assert_eq!(r.get_path("#1").deparse(), r#"{"count":1,"hi":1.0,"lo":0.0}"#);
+    assert_eq!(r.get_path("#2/hi").deparse(), "null");
+    assert_eq!(r.get_path("#2/count").coerce_f64(), 1.0);
+}
+
+#[test]
+fn test_histogram_log_exact_powers() {
+    // The float log of each of these comes out just under the integer.
+    for &(base, v) in &[(10.0, 1e3), (10.0, 1e6), (10.0, 1e15), (3.0, 243.0), (3.0, 59049.0)] {
+        let spec = format!("histogram,log:{},x", base);
+        let r = finish(&spec, &xs(&[v]));
+        assert_eq!(r.get_path("#0/lo").coerce_f64(), v, "{} in base {}", v, base);
+    }
+    let r = finish("histogram,log:10,x", &xs(&[999.0, 1000.0, 0.0, 0.001]));
+    assert_eq!(r.deparse(), r#"[{"count":1,"hi":0.0},{"count":1,"hi":0.01,"lo":0.001},{"count":1,"hi":1000.0,"lo":100.0},{"count":1,"hi":10000.0,"lo":1000.0}]"#);
+}
+
+#[test]
+fn test_percentiles() {
+    let rs = xs(&scrambled(100));
+    assert_eq!(finish("percs,0:50:90:100,x", &rs).deparse(), r#"{"0":0.0,"100":99.0,"50":50.0,"90":90.0}"#);
+    assert_eq!(finish_merged("percs,50:90,x", &rs, 6), finish("percs,50:90,x", &rs));
+    assert_eq!(finish("perc,90,x", &rs), finish("percs,90,x", &rs).get_path("90"));
+    assert!(super::REGISTRY.find("percs", &["50:101", "x"]).is_err());
+}
+
+#[test]
+fn test_median() {
+    assert_eq!(finish("median,x", &xs(&[3.0, 1.0, 2.0])).coerce_f64(), 2.0);
+    assert_eq!(finish("median,x", &xs(&[4.0, 1.0, 3.0, 2.0])).coerce_f64(), 2.5);
+    assert_eq!(finish_merged("median,x", &xs(&[4.0, 1.0, 3.0, 2.0]), 2).coerce_f64(), 2.5);
+    assert_eq!(finish("median,x", &[]).deparse(), "null");
+}
+
+#[test]
+fn test_mode() {
+    let rs = records(&[r#"{"x":"b"}"#, r#"{"x":"a"}"#, r#"{"x":"b"}"#, r#"{"x":"c"}"#, r#"{"x":"a"}"#]);
+    // Tied: the lexically first.
+    assert_eq!(finish("mode,x", &rs).deparse(), r#""a""#);
+    assert_eq!(finish("mode,x", &rs[0..4]).deparse(), r#""b""#);
+    assert_eq!(finish_merged("mode,x", &rs, 3), finish("mode,x", &rs));
+    assert_eq!(finish("mode,x", &[]).deparse(), "null");
+}
+
+#[test]
+fn test_where() {
+    let rs = xs(&[1.0, 5.0, 2.0, 7.0]);
+    assert_eq!(finish("where,{{x}} > 1.5,count", &rs).deparse(), "3");
+    assert_eq!(finish("where,{{x}} > 1.5,sum,x", &rs).coerce_f64(), 14.0);
+    // Escaped commas stay in the code.
+    assert_eq!(finish(r#"where,lua:return math.max(r["x"]\,3) > 3,count"#, &rs).deparse(), "2");
+    assert_eq!(finish_merged("where,{{x}} > 1.5,sum,x", &rs, 2).coerce_f64(), 14.0);
+}
+
+#[test]
+fn test_expr() {
+    let rs = xs(&[1.0, 5.0, 2.0, 7.0]);
+    assert_eq!(finish("expr,{{x}} * 2,sum", &rs).coerce_f64(), 30.0);
+    assert_eq!(finish(r#"expr,lua:return math.max(r["x"]\,3),max"#, &rs).coerce_f64(), 7.0);
+    assert_eq!(finish(r#"expr,lua:return math.max(r["x"]\,3),sum"#, &rs).coerce_f64(), 18.0);
+    assert_eq!(finish_merged("expr,{{x}} * 2,sum", &rs, 3).coerce_f64(), 30.0);
+}
+
+#[test]
+fn test_eval() {
+    let rs = xs(&[1.0, 5.0, 2.0, 7.0]);
+    let sum = "eval,{{state}} = 0,{{state}} = {{state}} + {{record/x}},{{state}},{{state}} = {{state}} + {{other}}";
+    assert_eq!(finish(sum, &rs).coerce_f64(), 15.0);
+    assert_eq!(finish_merged(sum, &rs, 3).coerce_f64(), 15.0);
+    // Escaped commas stay in the code.
+    let max = r#"eval,lua:r["state"] = 0,lua:r["state"] = math.max(r["state"]\,r["record"]["x"]),{{state}},lua:r["state"] = math.max(r["state"]\,r["other"])"#;
+    assert_eq!(finish(max, &rs).coerce_f64(), 7.0);
+    assert_eq!(finish_merged(max, &rs, 4).coerce_f64(), 7.0);
+    // Merge code is optional, but then partials can't be merged.
+    let sum = "eval,{{state}} = 0,{{state}} = {{state}} + {{record/x}},{{state}}";
+    assert_eq!(finish(sum, &rs).coerce_f64(), 15.0);
+    let agg = super::REGISTRY.find("eval", &["{{state}} = 0", "{{state}} = 1", "{{state}}"]).unwrap_or_else(|e| e.panic());
+    assert!(!agg.mergeable());
+    assert!(super::REGISTRY.find("eval", &["{{state}} = 0", "{{state}} = 1", "{{state}}", "{{state}}", "{{state}}"]).is_err());
+}
+
+fn xis() -> Vec<Record> {
+    return records(&[
+        r#"{"i":0,"s":"m","x":3}"#,
+        r#"{"i":1,"s":"z","x":10}"#,
+        r#"{"i":2,"s":"a","x":3}"#,
+        r#"{"i":3,"s":"b","x":2}"#,
+        r#"{"i":4,"s":"z","x":10}"#,
+        r#"{"i":5,"s":"c","x":3}"#,
+    ]);
+}
+
+#[test]
+fn test_top_k() {
+    let rs = xis();
+    let cases = [
+        ("topk,3,x", "[10,10,3]"),
+        ("bottomk,3,x", "[2,3,3]"),
+        ("ltopk,2,s", r#"["z","z"]"#),
+        ("lbottomk,2,s", r#"["a","b"]"#),
+        ("firstn,2,x", "[3,10]"),
+        ("lastn,2,x", "[10,3]"),
+        ("topk,10,x", "[10,10,3,3,3,2]"),
+        ("topk,0,x", "[]"),
+    ];
+    for &(spec, expected) in &cases {
+        assert_eq!(finish(spec, &rs).deparse(), expected, "{}", spec);
+        for &parts in &[2, 6] {
+            assert_eq!(finish_merged(spec, &rs, parts).deparse(), expected, "{} in {} parts", spec, parts);
+        }
+    }
+}
+
+#[test]
+fn test_record_for_top_k() {
+    let rs = xis();
+    let is = |r: Record| r.expect_array().iter().map(|r| r.get_path("i").deparse().to_string()).collect::<Vec<_>>().join(" ");
+    // Ties go to earlier records.
+    let cases = [
+        ("recfortopk,3,x", "1 4 0"),
+        ("recforbottomk,2,x", "3 0"),
+        ("recforltopk,3,s", "1 4 0"),
+        ("recforlbottomk,2,s", "2 3"),
+    ];
+    for &(spec, expected) in &cases {
+        assert_eq!(is(finish(spec, &rs)), expected, "{}", spec);
+        assert_eq!(is(finish_merged(spec, &rs, 3)), expected, "{} merged", spec);
+    }
+}
+
+#[test]
+fn test_time_series() {
+    // Out of order, and with a string timestamp.
+    let rs = records(&[r#"{"t":10,"v":20}"#, r#"{"t":"1970-01-01T00:00:00Z","v":10}"#, r#"{"t":30,"v":0}"#]);
+    assert_close("delta,v,t", &rs, -10.0);
+    assert_close("rate,v,t", &rs, -10.0 / 30.0);
+    // 10 held for 10s, then 20 for 20s.
+    assert_close("twavg,v,t", &rs, 500.0 / 30.0);
+    // Weights 1/8, 1/4 and 1 by age.
+    assert_close("ewma,10s,v,t", &rs, (10.0 / 8.0 + 20.0 / 4.0) / (1.0 / 8.0 + 1.0 / 4.0 + 1.0));
+    assert_close("ewma,10s,v,t", &rs[0..1], 20.0);
+    assert!(super::REGISTRY.find("ewma", &["0s", "v", "t"]).is_err());
+    assert!(super::REGISTRY.find("ewma", &["10parsecs", "v", "t"]).is_err());
+}
+
+#[test]
+fn test_weighted() {
+    let rs = records(&[r#"{"v":1,"w":1}"#, r#"{"v":2,"w":3}"#, r#"{"v":10,"w":0}"#, r#"{"v":4,"w":4}"#]);
+    assert_close("wsum,v,w", &rs, 23.0);
+    assert_close("wavg,v,w", &rs, 23.0 / 8.0);
+    assert_close("wstddev,v,w", &rs, (77.0f64 / 8.0 - (23.0f64 / 8.0).powi(2)).sqrt());
+    // The first value whose cumulative weight passes the share (the zero
+    // weight value never does).
+    for &(p, v) in &[(0, 1.0), (12, 1.0), (13, 2.0), (49, 2.0), (50, 4.0), (100, 4.0)] {
+        assert_close(&format!("wperc,{},v,w", p), &rs, v);
+    }
+    // Equal weights agree with perc.
+    let rs = records(&[r#"{"v":5,"w":2}"#, r#"{"v":1,"w":2}"#, r#"{"v":3,"w":2}"#, r#"{"v":2,"w":2}"#]);
+    for p in &["0", "25", "50", "90", "100"] {
+        assert_eq!(finish(&format!("wperc,{},v,w", p), &rs), finish(&format!("perc,{},v", p), &rs), "{}", p);
+    }
+}
+
+#[test]
+fn test_sample() {
+    let rs = xs(&scrambled(100));
+    let sample = |spec: &str| finish(spec, &rs).expect_array().iter().map(|v| v.coerce_f64()).collect::<Vec<_>>();
+    let s = sample("sample,10,x,7");
+    assert_eq!(s.len(), 10);
+    assert_eq!(sample("sample,10,x,7"), s);
+    // In input order.
+    let positions: Vec<_> = s.iter().map(|v| rs.iter().position(|r| r.get_path("x").coerce_f64() == *v).unwrap()).collect();
+    let mut sorted = positions.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(positions, sorted);
+    assert_eq!(sample("sample,200,x").len(), 100);
+
+    let merged = finish_merged("sample,10,x,7", &rs, 4);
+    assert_eq!(merged.expect_array().len(), 10);
+    assert_eq!(finish_merged("sample,10,x,7", &rs, 4).deparse(), merged.deparse());
+    assert!(super::REGISTRY.find("sample", &["10", "x", "nope"]).is_err());
+}
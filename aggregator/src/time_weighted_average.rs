@@ -0,0 +1,63 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use std::collections::BTreeMap;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::delta::TimeSeriesArgs;
+
+// Points by timestamp (a later arrival for the same timestamp wins), since
+// out of order input means nothing can be integrated until the end.
+pub(crate) type PointsState = BTreeMap<F64SortDishonorProxy, f64>;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = TimeSeriesArgs;
+    type State = PointsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["twavg"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute the time-weighted average of a gauge, each value holding until the next timestamp";
+    }
+
+    fn add(state: &mut PointsState, a: &TimeSeriesArgs, r: Record) {
+        state.insert(F64SortDishonorProxy(r.get_path(&a.time_key).coerce_timestamp()), r.get_path(&a.value_key).coerce_f64());
+    }
+
+    fn finish(state: PointsState, _a: &TimeSeriesArgs) -> Record {
+        let mut area = 0.0;
+        let mut prev: Option<(f64, f64)> = None;
+        for (t, v) in state.iter() {
+            if let Some((t0, v0)) = prev {
+                area += v0 * (t.0 - t0);
+            }
+            prev = Some((t.0, *v));
+        }
+        let (t0, t1) = match (state.keys().next(), state.keys().next_back()) {
+            (Some(t0), Some(t1)) => (t0.0, t1.0),
+            _ => return Record::null(),
+        };
+        if t1 == t0 {
+            return Record::from(state.values().next().cloned().unwrap());
+        }
+        return Record::from(area / (t1 - t0));
+    }
+
+    fn merge(state: &mut PointsState, other: PointsState, _a: &TimeSeriesArgs) {
+        state.extend(other);
+    }
+
+    fn save(state: PointsState, _a: &TimeSeriesArgs) -> Record {
+        return Record::from_vec(state.into_iter().map(|(t, v)| Record::from_vec(vec![Record::from(t.0), Record::from(v)])).collect());
+    }
+
+    fn load(r: Record, _a: &TimeSeriesArgs) -> PointsState {
+        return r.expect_array().iter().map(|p| (F64SortDishonorProxy(p.get_path("#0").coerce_f64()), p.get_path("#1").coerce_f64())).collect();
+    }
+}
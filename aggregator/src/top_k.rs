@@ -0,0 +1,152 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use std::cmp::Ord;
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+#[derive(RegistryArgs)]
+pub(crate) struct NKeyArgs {
+    pub(crate) n: usize,
+    pub(crate) key: Arc<str>,
+}
+
+// Ordered by key and then earliest first (so ties keep earlier input).
+#[derive(Clone)]
+struct Entry<K> {
+    k: K,
+    seq: u64,
+    v: Record,
+}
+
+impl<K: Ord> Entry<K> {
+    fn cmp_key(&self) -> (&K, Reverse<u64>) {
+        return (&self.k, Reverse(self.seq));
+    }
+}
+
+impl<K: Ord> PartialEq for Entry<K> {
+    fn eq(&self, other: &Entry<K>) -> bool {
+        return self.cmp_key() == other.cmp_key();
+    }
+}
+
+impl<K: Ord> Eq for Entry<K> {
+}
+
+impl<K: Ord> PartialOrd for Entry<K> {
+    fn partial_cmp(&self, other: &Entry<K>) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl<K: Ord> Ord for Entry<K> {
+    fn cmp(&self, other: &Entry<K>) -> Ordering {
+        return self.cmp_key().cmp(&other.cmp_key());
+    }
+}
+
+// The n greatest, kept in a min-heap so the worst is evicted first.
+#[derive(Clone)]
+pub(crate) struct TopKState<K: Ord> {
+    heap: BinaryHeap<Reverse<Entry<K>>>,
+    seq: u64,
+}
+
+impl<K: Ord> Default for TopKState<K> {
+    fn default() -> Self {
+        return TopKState {
+            heap: BinaryHeap::new(),
+            seq: 0,
+        };
+    }
+}
+
+impl<K: Ord> TopKState<K> {
+    pub(crate) fn add(&mut self, n: usize, k: K, v: Record) {
+        let e = Entry {
+            k: k,
+            seq: self.seq,
+            v: v,
+        };
+        self.seq += 1;
+        if self.heap.len() < n {
+            self.heap.push(Reverse(e));
+            return;
+        }
+        if let Some(worst) = self.heap.peek() {
+            if e <= worst.0 {
+                return;
+            }
+        }
+        if n > 0 {
+            self.heap.pop();
+            self.heap.push(Reverse(e));
+        }
+    }
+
+    // Best first.
+    pub(crate) fn finish(self) -> Record {
+        return Record::from_vec(self.heap.into_sorted_vec().into_iter().map(|e| e.0.v).collect());
+    }
+
+    pub(crate) fn merge(&mut self, n: usize, other: TopKState<K>) {
+        for e in other.heap.into_sorted_vec().into_iter().rev() {
+            self.add(n, e.0.k, e.0.v);
+        }
+    }
+
+    pub(crate) fn save(self) -> Record {
+        return self.finish();
+    }
+
+    pub(crate) fn load<F: Fn(&Record) -> K>(r: Record, n: usize, f: F) -> Self {
+        let mut state = TopKState::default();
+        for v in r.expect_array().iter() {
+            state.add(n, f(v), v.clone());
+        }
+        return state;
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = NKeyArgs;
+    type State = TopKState<F64SortDishonorProxy>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["topk"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "track the n numerically greatest values";
+    }
+
+    fn add(state: &mut TopKState<F64SortDishonorProxy>, a: &NKeyArgs, r: Record) {
+        let v = r.get_path(&a.key);
+        state.add(a.n, F64SortDishonorProxy(v.coerce_f64()), v);
+    }
+
+    fn finish(state: TopKState<F64SortDishonorProxy>, _a: &NKeyArgs) -> Record {
+        return state.finish();
+    }
+
+    fn merge(state: &mut TopKState<F64SortDishonorProxy>, other: TopKState<F64SortDishonorProxy>, a: &NKeyArgs) {
+        state.merge(a.n, other);
+    }
+
+    fn save(state: TopKState<F64SortDishonorProxy>, _a: &NKeyArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, a: &NKeyArgs) -> TopKState<F64SortDishonorProxy> {
+        return TopKState::load(r, a.n, |v| F64SortDishonorProxy(v.coerce_f64()));
+    }
+}
@@ -0,0 +1,121 @@
+use record::Record;
+use record::RecordTrait;
+use registry::args::OneKeyRegistryArgs;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+// Count, mean, and central moment sums, updated per value (Welford, extended
+// to higher moments by Terriberry) and merged pairwise (Chan et al.).
+#[derive(Clone)]
+#[derive(Default)]
+pub(crate) struct MomentsState {
+    n: f64,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+}
+
+impl MomentsState {
+    pub(crate) fn add(&mut self, x: f64) {
+        let n1 = self.n;
+        self.n += 1.0;
+        let n = self.n;
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term1 = delta * delta_n * n1;
+        self.mean += delta_n;
+        self.m4 += term1 * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2 - 4.0 * delta_n * self.m3;
+        self.m3 += term1 * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term1;
+    }
+
+    pub(crate) fn merge(&mut self, other: MomentsState) {
+        if other.n == 0.0 {
+            return;
+        }
+        if self.n == 0.0 {
+            *self = other;
+            return;
+        }
+        let (a, b) = (self.clone(), other);
+        let n = a.n + b.n;
+        let delta = b.mean - a.mean;
+        let d2 = delta * delta;
+        self.n = n;
+        self.mean = a.mean + delta * b.n / n;
+        self.m2 = a.m2 + b.m2 + d2 * a.n * b.n / n;
+        self.m3 = a.m3 + b.m3 + d2 * delta * a.n * b.n * (a.n - b.n) / (n * n) + 3.0 * delta * (a.n * b.m2 - b.n * a.m2) / n;
+        self.m4 = a.m4 + b.m4 + d2 * d2 * a.n * b.n * (a.n * a.n - a.n * b.n + b.n * b.n) / (n * n * n) + 6.0 * d2 * (a.n * a.n * b.m2 + b.n * b.n * a.m2) / (n * n) + 4.0 * delta * (a.n * b.m3 - b.n * a.m3) / n;
+    }
+
+    pub(crate) fn save(self) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("count", Record::from(self.n));
+        r.set_path("mean", Record::from(self.mean));
+        r.set_path("m2", Record::from(self.m2));
+        r.set_path("m3", Record::from(self.m3));
+        r.set_path("m4", Record::from(self.m4));
+        return r;
+    }
+
+    pub(crate) fn load(r: Record) -> MomentsState {
+        return MomentsState {
+            n: r.get_path("count").coerce_f64(),
+            mean: r.get_path("mean").coerce_f64(),
+            m2: r.get_path("m2").coerce_f64(),
+            m3: r.get_path("m3").coerce_f64(),
+            m4: r.get_path("m4").coerce_f64(),
+        };
+    }
+
+    pub(crate) fn variance(&self, ddof: f64) -> f64 {
+        return self.m2 / (self.n - ddof);
+    }
+
+    pub(crate) fn skewness(&self) -> f64 {
+        return self.n.sqrt() * self.m3 / self.m2.powf(1.5);
+    }
+
+    pub(crate) fn kurtosis(&self) -> f64 {
+        return self.n * self.m4 / (self.m2 * self.m2) - 3.0;
+    }
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = OneKeyRegistryArgs;
+    type State = MomentsState;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["var", "variance"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute sample variance (n - 1 denominator) of numeric values";
+    }
+
+    fn add(state: &mut MomentsState, a: &OneKeyRegistryArgs, r: Record) {
+        state.add(r.get_path(&a.key).coerce_f64());
+    }
+
+    fn finish(state: MomentsState, _a: &OneKeyRegistryArgs) -> Record {
+        return Record::from(state.variance(1.0));
+    }
+
+    fn merge(state: &mut MomentsState, other: MomentsState, _a: &OneKeyRegistryArgs) {
+        state.merge(other);
+    }
+
+    fn save(state: MomentsState, _a: &OneKeyRegistryArgs) -> Record {
+        return state.save();
+    }
+
+    fn load(r: Record, _a: &OneKeyRegistryArgs) -> MomentsState {
+        return MomentsState::load(r);
+    }
+}
@@ -0,0 +1,55 @@
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+
+#[derive(RegistryArgs)]
+pub(crate) struct WeightedArgs {
+    pub(crate) value_key: Arc<str>,
+    pub(crate) weight_key: Arc<str>,
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = WeightedArgs;
+    type State = (f64, f64);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["wavg", "waverage"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute average of numeric values weighted by another key";
+    }
+
+    fn add(state: &mut (f64, f64), a: &WeightedArgs, r: Record) {
+        let v = r.get_path(&a.value_key).coerce_f64();
+        let w = r.get_path(&a.weight_key).coerce_f64();
+        state.0 += w;
+        state.1 += v * w;
+    }
+
+    fn finish(state: (f64, f64), _a: &WeightedArgs) -> Record {
+        return Record::from(state.1 / state.0);
+    }
+
+    fn merge(state: &mut (f64, f64), other: (f64, f64), _a: &WeightedArgs) {
+        state.0 += other.0;
+        state.1 += other.1;
+    }
+
+    fn save(state: (f64, f64), _a: &WeightedArgs) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("weight", Record::from(state.0));
+        r.set_path("sum", Record::from(state.1));
+        return r;
+    }
+
+    fn load(r: Record, _a: &WeightedArgs) -> (f64, f64) {
+        return (r.get_path("weight").coerce_f64(), r.get_path("sum").coerce_f64());
+    }
+}
@@ -0,0 +1,69 @@
+use record::F64SortDishonorProxy;
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::lexical_percentile::PercentileArg;
+
+#[derive(RegistryArgs)]
+pub(crate) struct Args {
+    percentile: PercentileArg,
+    value_key: Arc<str>,
+    weight_key: Arc<str>,
+}
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = Args;
+    type State = Vec<(F64SortDishonorProxy, f64, Record)>;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["wperc"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute a percentile of values sorted numerically, each counted with the weight from another key";
+    }
+
+    fn add(state: &mut Vec<(F64SortDishonorProxy, f64, Record)>, a: &Args, r: Record) {
+        let v = r.get_path(&a.value_key);
+        let w = r.get_path(&a.weight_key).coerce_f64();
+        state.push((F64SortDishonorProxy(v.coerce_f64()), w, v));
+    }
+
+    // As perc, i.e. the first value whose cumulative weight passes the
+    // percentile's share of the total.
+    fn finish(mut state: Vec<(F64SortDishonorProxy, f64, Record)>, a: &Args) -> Record {
+        state.sort_by(|(k1, _w1, _v1), (k2, _w2, _v2)| k1.cmp(k2));
+        let target = state.iter().map(|(_k, w, _v)| w).sum::<f64>() * a.percentile.0;
+        let mut cumulative = 0.0;
+        for (_k, w, v) in state.iter() {
+            cumulative += w;
+            if cumulative > target {
+                return v.clone();
+            }
+        }
+        // Only reached for the 100th percentile (or no weight at all), which
+        // is the greatest value that has any weight.
+        return state.iter().rev().find(|(_k, w, _v)| *w > 0.0).unwrap_or(state.last().unwrap()).2.clone();
+    }
+
+    fn merge(state: &mut Vec<(F64SortDishonorProxy, f64, Record)>, other: Vec<(F64SortDishonorProxy, f64, Record)>, _a: &Args) {
+        state.extend(other);
+    }
+
+    fn save(state: Vec<(F64SortDishonorProxy, f64, Record)>, _a: &Args) -> Record {
+        return Record::from_vec(state.into_iter().map(|(_k, w, v)| Record::from_vec(vec![v, Record::from(w)])).collect());
+    }
+
+    fn load(r: Record, _a: &Args) -> Vec<(F64SortDishonorProxy, f64, Record)> {
+        return r.expect_array().iter().map(|p| {
+            let v = p.get_path("#0");
+            return (F64SortDishonorProxy(v.coerce_f64()), p.get_path("#1").coerce_f64(), v);
+        }).collect();
+    }
+}
@@ -0,0 +1,52 @@
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::weighted_average::WeightedArgs;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = WeightedArgs;
+    type State = (f64, f64, f64);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["wstddev", "wsd"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute standard deviation of numeric values weighted by another key";
+    }
+
+    fn add(state: &mut (f64, f64, f64), a: &WeightedArgs, r: Record) {
+        let v = r.get_path(&a.value_key).coerce_f64();
+        let w = r.get_path(&a.weight_key).coerce_f64();
+        state.0 += w;
+        state.1 += w * v;
+        state.2 += w * v * v;
+    }
+
+    fn finish(state: (f64, f64, f64), _a: &WeightedArgs) -> Record {
+        return Record::from(((state.2 / state.0) - (state.1 / state.0).powi(2)).sqrt());
+    }
+
+    fn merge(state: &mut (f64, f64, f64), other: (f64, f64, f64), _a: &WeightedArgs) {
+        state.0 += other.0;
+        state.1 += other.1;
+        state.2 += other.2;
+    }
+
+    fn save(state: (f64, f64, f64), _a: &WeightedArgs) -> Record {
+        let mut r = Record::empty_hash();
+        r.set_path("weight", Record::from(state.0));
+        r.set_path("sum", Record::from(state.1));
+        r.set_path("sum2", Record::from(state.2));
+        return r;
+    }
+
+    fn load(r: Record, _a: &WeightedArgs) -> (f64, f64, f64) {
+        return (r.get_path("weight").coerce_f64(), r.get_path("sum").coerce_f64(), r.get_path("sum2").coerce_f64());
+    }
+}
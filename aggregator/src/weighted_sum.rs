@@ -0,0 +1,42 @@
+use record::Record;
+use record::RecordTrait;
+use super::AggregatorBe;
+use super::AggregatorRegistrant;
+use super::weighted_average::WeightedArgs;
+
+pub(crate) type Impl = AggregatorRegistrant<ImplBe>;
+
+pub(crate) struct ImplBe;
+
+impl AggregatorBe for ImplBe {
+    type Args = WeightedArgs;
+    type State = f64;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["wsum"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "compute sum of numeric values weighted by another key";
+    }
+
+    fn add(state: &mut f64, a: &WeightedArgs, r: Record) {
+        *state += r.get_path(&a.value_key).coerce_f64() * r.get_path(&a.weight_key).coerce_f64();
+    }
+
+    fn finish(state: f64, _a: &WeightedArgs) -> Record {
+        return Record::from(state);
+    }
+
+    fn merge(state: &mut f64, other: f64, _a: &WeightedArgs) {
+        *state += other;
+    }
+
+    fn save(state: f64, _a: &WeightedArgs) -> Record {
+        return Record::from(state);
+    }
+
+    fn load(r: Record, _a: &WeightedArgs) -> f64 {
+        return r.coerce_f64();
+    }
+}
@@ -3,16 +3,87 @@ extern crate wns;
 
 use std::collections::VecDeque;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
 use stream::Entry;
 use stream::Stream;
 use stream::StreamTrait;
 use wns::WaitNotifyState;
 
+#[derive(Clone)]
+pub struct BgopConfig {
+    pub max_buffer: usize,
+    pub batch: usize,
+    pub on_close: Option<Arc<Fn(BgopMetrics) + Send + Sync>>,
+}
+
+impl Default for BgopConfig {
+    fn default() -> Self {
+        return BgopConfig {
+            max_buffer: 1024,
+            batch: 64,
+            on_close: None,
+        };
+    }
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct OneBufferMetrics {
+    pub high_water: usize,
+    pub stall: Duration,
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct BgopMetrics {
+    pub fe_to_be: OneBufferMetrics,
+    pub be_to_fe: OneBufferMetrics,
+}
+
 #[derive(Default)]
 struct OneBuffer {
     buf: VecDeque<Entry>,
     rclosed: bool,
     closed: bool,
+    metrics: OneBufferMetrics,
+}
+
+impl OneBuffer {
+    // Push a whole batch if there is any room at all (so batches larger than
+    // the bound can't wedge us).  Returns None if we're full and should wait.
+    fn push_batch(&mut self, max_buffer: usize, es: &mut Vec<Entry>, stalled: &mut Option<Instant>) -> Option<bool> {
+        if self.rclosed {
+            es.clear();
+            return Some(false);
+        }
+        if self.buf.len() < max_buffer {
+            self.buf.extend(es.drain(..));
+            if self.buf.len() > self.metrics.high_water {
+                self.metrics.high_water = self.buf.len();
+            }
+            if let Some(t) = stalled.take() {
+                self.metrics.stall += t.elapsed();
+            }
+            return Some(true);
+        }
+        if stalled.is_none() {
+            *stalled = Some(Instant::now());
+        }
+        return None;
+    }
+}
+
+// Set alongside the rclosed flags in the state, but readable without the lock
+// so writers holding back a partial batch see the close immediately.
+#[derive(Default)]
+struct RcloseFlags {
+    fe_to_be: AtomicBool,
+    be_to_fe: AtomicBool,
 }
 
 #[derive(Default)]
@@ -21,15 +92,34 @@ struct BgopState {
     be_to_fe: OneBuffer,
 }
 
+impl BgopState {
+    fn metrics(&self) -> BgopMetrics {
+        return BgopMetrics {
+            fe_to_be: self.fe_to_be.metrics.clone(),
+            be_to_fe: self.be_to_fe.metrics.clone(),
+        };
+    }
+}
+
 pub struct BgopRbe {
     state: Arc<WaitNotifyState<BgopState>>,
+    rclosed: Arc<RcloseFlags>,
+    config: BgopConfig,
+    pending: VecDeque<Entry>,
 }
 
 impl BgopRbe {
-    pub fn read(&self) -> Option<Entry> {
+    pub fn read(&mut self) -> Option<Entry> {
+        if let Some(e) = self.pending.pop_front() {
+            return Some(e);
+        }
+        let batch = self.config.batch;
+        let pending = &mut self.pending;
         return self.state.wait(&mut |buffers| {
-            if let Some(e) = buffers.fe_to_be.buf.pop_front() {
-                return (Some(Some(e)), true);
+            if !buffers.fe_to_be.buf.is_empty() {
+                let n = std::cmp::min(batch, buffers.fe_to_be.buf.len());
+                pending.extend(buffers.fe_to_be.buf.drain(..n));
+                return (Some(pending.pop_front()), true);
             }
             if buffers.fe_to_be.closed {
                 return (Some(None), false);
@@ -38,41 +128,74 @@ impl BgopRbe {
         });
     }
 
-    pub fn rclose(&self) {
+    pub fn rclose(&mut self) {
+        self.pending.clear();
+        self.rclosed.fe_to_be.store(true, Ordering::SeqCst);
         self.state.write(|buffers| {
             buffers.fe_to_be.rclosed = true;
             buffers.fe_to_be.buf.clear();
         });
     }
+
+    pub fn metrics(&self) -> BgopMetrics {
+        return self.state.read(BgopState::metrics);
+    }
 }
 
 pub struct BgopWbe {
     state: Arc<WaitNotifyState<BgopState>>,
+    rclosed: Arc<RcloseFlags>,
+    config: BgopConfig,
+    pending: Vec<Entry>,
 }
 
 impl BgopWbe {
-    pub fn write(&self, e: Entry) -> bool {
+    pub fn write(&mut self, e: Entry) -> bool {
+        if self.rclosed.be_to_fe.load(Ordering::SeqCst) {
+            self.pending.clear();
+            return false;
+        }
+        self.pending.push(e);
+        if self.pending.len() < self.config.batch {
+            return true;
+        }
+        return self.flush();
+    }
+
+    fn flush(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return true;
+        }
+        let max_buffer = self.config.max_buffer;
+        let pending = &mut self.pending;
+        let mut stalled = None;
         return self.state.wait(&mut |buffers| {
-            if buffers.be_to_fe.rclosed {
-                return (Some(false), false);
-            }
-            if buffers.be_to_fe.buf.len() < 1024 {
-                buffers.be_to_fe.buf.push_back(e.clone());
-                return (Some(true), true);
-            }
-            return (None, false);
+            return match buffers.be_to_fe.push_batch(max_buffer, pending, &mut stalled) {
+                Some(true) => (Some(true), true),
+                Some(false) => (Some(false), false),
+                None => (None, false),
+            };
         });
     }
 
-    pub fn close(self) {
+    pub fn close(mut self) {
+        self.flush();
         self.state.write(&mut |buffers: &mut BgopState| {
             buffers.be_to_fe.closed = true;
         });
     }
+
+    pub fn metrics(&self) -> BgopMetrics {
+        return self.state.read(BgopState::metrics);
+    }
 }
 
 pub struct BgopFe {
     state: Arc<WaitNotifyState<BgopState>>,
+    rclosed_flags: Arc<RcloseFlags>,
+    config: BgopConfig,
+    pending: Vec<Entry>,
+    rclosed: bool,
 }
 
 impl BgopFe {
@@ -98,6 +221,7 @@ impl BgopFe {
                 Ret::Ferry(es) => {
                     for e in es {
                         if !w(e) {
+                            self.rclosed_flags.be_to_fe.store(true, Ordering::SeqCst);
                             self.state.write(|buffers| {
                                 buffers.be_to_fe.rclosed = true;
                                 buffers.be_to_fe.buf.clear();
@@ -112,25 +236,42 @@ impl BgopFe {
             }
         }
     }
+
+    fn flush(&mut self, w: &mut FnMut(Entry) -> bool) -> bool {
+        if self.pending.is_empty() {
+            return !self.rclosed;
+        }
+        let mut pending = std::mem::replace(&mut self.pending, Vec::new());
+        let max_buffer = self.config.max_buffer;
+        let mut stalled = None;
+        let ret = self.ferry(|_os_closed, buffers| {
+            return buffers.fe_to_be.push_batch(max_buffer, &mut pending, &mut stalled);
+        }, w);
+        if !ret {
+            self.rclosed = true;
+        }
+        return ret;
+    }
 }
 
 impl StreamTrait for BgopFe {
     fn write(&mut self, e: Entry, w: &mut FnMut(Entry) -> bool) -> bool {
-        return self.ferry(|_os_closed, buffers| {
-            if buffers.fe_to_be.rclosed {
-                return Some(false);
-            }
-
-            if buffers.fe_to_be.buf.len() < 1024 {
-                buffers.fe_to_be.buf.push_back(e.clone());
-                return Some(true);
-            }
-
-            return None;
-        }, w);
+        if self.rclosed_flags.fe_to_be.load(Ordering::SeqCst) {
+            self.rclosed = true;
+            self.pending.clear();
+        }
+        if self.rclosed {
+            return false;
+        }
+        self.pending.push(e);
+        if self.pending.len() < self.config.batch {
+            return true;
+        }
+        return self.flush(w);
     }
 
-    fn close(self: Box<BgopFe>, w: &mut FnMut(Entry) -> bool) {
+    fn close(mut self: Box<BgopFe>, w: &mut FnMut(Entry) -> bool) {
+        self.flush(w);
         self.state.write(|buffers| {
             buffers.fe_to_be.closed = true;
         });
@@ -140,23 +281,47 @@ impl StreamTrait for BgopFe {
             }
             return None;
         }, w);
+        if let Some(ref f) = self.config.on_close {
+            f(self.state.read(BgopState::metrics));
+        }
     }
 }
 
 pub fn new() -> (Stream, BgopRbe, BgopWbe) {
+    return new_with(BgopConfig::default());
+}
+
+pub fn new_with(config: BgopConfig) -> (Stream, BgopRbe, BgopWbe) {
+    assert!(config.max_buffer > 0, "bgop buffer must be positive");
+    assert!(config.batch > 0, "bgop batch must be positive");
+
     let state = Arc::new(WaitNotifyState::new(BgopState::default()));
+    let rclosed = Arc::new(RcloseFlags::default());
 
     let fe = BgopFe {
         state: state.clone(),
+        rclosed_flags: rclosed.clone(),
+        config: config.clone(),
+        pending: Vec::new(),
+        rclosed: false,
     };
 
     let rbe = BgopRbe {
         state: state.clone(),
+        rclosed: rclosed.clone(),
+        config: config.clone(),
+        pending: VecDeque::new(),
     };
 
     let wbe = BgopWbe {
         state: state.clone(),
+        rclosed: rclosed,
+        config: config,
+        pending: Vec::new(),
     };
 
     return (Stream::new(fe), rbe, wbe);
 }
+
+#[cfg(test)]
+mod tests;
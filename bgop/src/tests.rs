@@ -0,0 +1,130 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use stream::Entry;
+use super::BgopConfig;
+use super::BgopMetrics;
+use super::BgopRbe;
+use super::BgopWbe;
+
+fn line(i: usize) -> Entry {
+    return Entry::Line(Arc::from(i.to_string()));
+}
+
+fn unline(e: Entry) -> usize {
+    return e.deparse().parse().unwrap();
+}
+
+// Echo everything back, returning how many entries were read.
+fn echo(mut rbe: BgopRbe, mut wbe: BgopWbe) -> thread::JoinHandle<usize> {
+    return thread::spawn(move || {
+        let mut n = 0;
+        while let Some(e) = rbe.read() {
+            n += 1;
+            if !wbe.write(e) {
+                rbe.rclose();
+                break;
+            }
+        }
+        wbe.close();
+        return n;
+    });
+}
+
+fn run(config: BgopConfig, n: usize) -> Vec<usize> {
+    let (mut fe, rbe, wbe) = super::new_with(config);
+    let t = echo(rbe, wbe);
+    let mut out = Vec::new();
+    for i in 0..n {
+        assert!(fe.write(line(i), &mut |e| {
+            out.push(unline(e));
+            return true;
+        }));
+    }
+    fe.close(&mut |e| {
+        out.push(unline(e));
+        return true;
+    });
+    assert_eq!(t.join().unwrap(), n);
+    return out;
+}
+
+#[test]
+fn test_order_across_batches() {
+    for &(buffer, batch) in &[(1, 1), (1, 7), (3, 2), (1024, 64)] {
+        let config = BgopConfig {
+            max_buffer: buffer,
+            batch: batch,
+            on_close: None,
+        };
+        let out = run(config, 100);
+        assert_eq!(out, (0..100).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_buffer_limit() {
+    let seen = Arc::new(Mutex::new(None));
+    let seen2 = seen.clone();
+    let config = BgopConfig {
+        max_buffer: 4,
+        batch: 3,
+        on_close: Some(Arc::new(move |m: BgopMetrics| {
+            *seen2.lock().unwrap() = Some(m);
+        })),
+    };
+    let out = run(config, 1000);
+    assert_eq!(out.len(), 1000);
+    let m = seen.lock().unwrap().take().unwrap();
+    // A whole batch is admitted whenever the buffer is below its bound.
+    assert!(m.fe_to_be.high_water >= 1);
+    assert!(m.fe_to_be.high_water < 4 + 3);
+    assert!(m.be_to_fe.high_water < 4 + 3);
+}
+
+#[test]
+fn test_rclose_seen_before_batch_flush() {
+    let config = BgopConfig {
+        max_buffer: 16,
+        batch: 8,
+        on_close: None,
+    };
+    let (mut fe, mut rbe, wbe) = super::new_with(config);
+    rbe.rclose();
+    // The first write would otherwise sit in a partial batch and report
+    // success.
+    assert!(!fe.write(line(0), &mut |_e| true));
+    drop(rbe);
+    wbe.close();
+    fe.close(&mut |_e| true);
+}
+
+#[test]
+fn test_downstream_close_stops_backend() {
+    let config = BgopConfig {
+        max_buffer: 2,
+        batch: 4,
+        on_close: None,
+    };
+    let (mut fe, rbe, wbe) = super::new_with(config);
+    let t = echo(rbe, wbe);
+    let mut out = Vec::new();
+    let mut i = 0;
+    loop {
+        let ok = fe.write(line(i), &mut |e| {
+            out.push(unline(e));
+            return out.len() < 5;
+        });
+        i += 1;
+        if !ok || i > 10000 {
+            break;
+        }
+    }
+    fe.close(&mut |e| {
+        out.push(unline(e));
+        return false;
+    });
+    t.join().unwrap();
+    assert!(i < 10000);
+    assert_eq!(&out[0..5], &[0, 1, 2, 3, 4]);
+}
@@ -0,0 +1,59 @@
+use record::Record;
+use registry::args::OneKeyRegistryArgs;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::ClumperBe;
+use super::ClumperRegistrant;
+
+pub type Impl = ClumperRegistrant<ImplBe>;
+
+pub struct ImplBe();
+
+impl ClumperBe for ImplBe {
+    type Args = OneKeyRegistryArgs;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["adjacent", "run"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "bucket runs of records with equal values of one key, closing each bucket when the value changes (for sorted input)";
+    }
+
+    fn stream(a: &OneKeyRegistryArgs, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let k = a.key.clone();
+
+        return stream::closures(
+            None,
+            move |s: &mut Option<(Record, Stream)>, e, w| {
+                let r = e.parse();
+
+                let v = r.get_path(&k);
+
+                let same = match s {
+                    Some((v0, _substream)) => *v0 == v,
+                    None => false,
+                };
+                if !same {
+                    if let Some((_v0, substream)) = s.take() {
+                        substream.close(w);
+                    }
+                    *s = Some((v.clone(), bsw(vec![(k.clone(), v)])));
+                }
+
+                // Disregard flow since one substream ending does
+                // not mean we're done (e.g.  each substream could
+                // be head -n 1).
+                s.as_mut().unwrap().1.write(Entry::Record(r), w);
+
+                return true;
+            },
+            |s, w| {
+                if let Some((_v, substream)) = s {
+                    substream.close(w);
+                }
+            },
+        );
+    }
+}
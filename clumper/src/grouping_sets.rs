@@ -0,0 +1,75 @@
+use record::Record;
+use std::collections::HashMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::BoxedClumper;
+use super::ClumperInbox;
+
+// Value used for keys that are rolled up in a grouping set.
+pub const ALL: &str = "ALL";
+
+#[derive(Clone)]
+struct GroupingSetsClumper {
+    keys: Arc<Vec<Arc<str>>>,
+    // Each set is a mask of which keys are kept.
+    sets: Arc<Vec<Vec<bool>>>,
+}
+
+pub fn cube(keys: Vec<Arc<str>>) -> BoxedClumper {
+    let n = keys.len();
+    let sets = (0..(1usize << n)).rev().map(|mask| (0..n).map(|i| mask & (1 << (n - 1 - i)) != 0).collect()).collect();
+    return Box::new(GroupingSetsClumper {
+        keys: Arc::new(keys),
+        sets: Arc::new(sets),
+    });
+}
+
+pub fn rollup(keys: Vec<Arc<str>>) -> BoxedClumper {
+    let n = keys.len();
+    let sets = (0..=n).rev().map(|len| (0..n).map(|i| i < len).collect()).collect();
+    return Box::new(GroupingSetsClumper {
+        keys: Arc::new(keys),
+        sets: Arc::new(sets),
+    });
+}
+
+impl ClumperInbox for GroupingSetsClumper {
+    fn stream(&self, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let keys = self.keys.clone();
+        let sets = self.sets.clone();
+
+        return stream::closures(
+            HashMap::new(),
+            move |s, e, w| {
+                let r = e.parse();
+
+                let vs: Vec<_> = keys.iter().map(|k| r.get_path(k)).collect();
+
+                for set in sets.iter() {
+                    let bvs: Vec<_> = vs.iter().zip(set.iter()).map(|(v, &keep)| if keep { v.clone() } else { Record::from(ALL) }).collect();
+
+                    let substream = s.entry(bvs.clone()).or_insert_with(|| {
+                        return bsw(keys.iter().cloned().zip(bvs.into_iter()).collect());
+                    });
+
+                    // Disregard flow since one substream ending does
+                    // not mean we're done (e.g.  each substream could
+                    // be head -n 1).
+                    substream.write(Entry::Record(r.clone()), w);
+                }
+
+                return true;
+            },
+            |s, w| {
+                for (_, substream) in s.into_iter() {
+                    substream.close(w);
+                }
+            },
+        );
+    }
+
+    fn box_clone(&self) -> BoxedClumper {
+        return Box::new(self.clone());
+    }
+}
@@ -0,0 +1,86 @@
+use record::Record;
+use record::RecordTrait;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::ClumperBe;
+use super::ClumperRegistrant;
+use validates::ValidationResult;
+
+pub struct Args {
+    count: usize,
+    keys: Vec<Arc<str>>,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",count,key[,key...]";
+    }
+
+    fn argct() -> usize {
+        return 2;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        return Result::Ok(Args {
+            count: <usize as RegistryArg>::parse(args[0]).map_err(|e| e.label("While parsing count"))?,
+            keys: args[1..].iter().map(|&k| Arc::from(k)).collect(),
+        });
+    }
+}
+
+pub type Impl = ClumperRegistrant<ImplBe>;
+
+pub struct ImplBe();
+
+impl ClumperBe for ImplBe {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["hash"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "bucket records into a specified number of shards by a stable hash of one or more keys (bucket field 'shard')";
+    }
+
+    fn stream(a: &Args, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let n = std::cmp::max(1, a.count);
+        let keys = a.keys.clone();
+        let substreams: Vec<Option<Stream>> = (0..n).map(|_| None).collect();
+
+        return stream::closures(
+            substreams,
+            move |s, e, w| {
+                let r = e.parse();
+
+                // A single key hashes its value alone, several the array of
+                // their values.
+                let h = match keys.len() {
+                    1 => r.get_path(&keys[0]).stable_hash(),
+                    _ => Record::from_vec(keys.iter().map(|k| r.get_path(k)).collect()).stable_hash(),
+                };
+                let i = (h % (n as u64)) as usize;
+                let substream = s[i].get_or_insert_with(|| bsw(vec![(Arc::from("shard"), Record::from(i as i64))]));
+
+                // Disregard flow since one substream ending does
+                // not mean we're done (e.g.  each substream could
+                // be head -n 1).
+                substream.write(Entry::Record(r), w);
+
+                return true;
+            },
+            |s, w| {
+                for substream in s.into_iter().flatten() {
+                    substream.close(w);
+                }
+            },
+        );
+    }
+}
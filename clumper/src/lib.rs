@@ -1,5 +1,6 @@
 #[macro_use]
 extern crate lazy_static;
+extern crate misc;
 extern crate record;
 #[macro_use]
 extern crate registry;
@@ -9,6 +10,8 @@ extern crate registry_args_derive;
 extern crate stream;
 extern crate validates;
 
+pub mod grouping_sets;
+
 use record::Record;
 use registry::Registrant;
 use registry_args::RegistryArgs;
@@ -20,8 +23,14 @@ pub type BoxedClumper = Box<ClumperInbox>;
 
 registry! {
     BoxedClumper,
+    adjacent,
+    hash,
     key,
+    lru,
     round_robin,
+    session,
+    sliding,
+    time,
     window,
 }
 
@@ -100,3 +109,6 @@ pub fn stream<F: Fn(Vec<(Arc<str>, Record)>) -> Stream + 'static>(cws: &Vec<Boxe
 
     return bsw(vec![]);
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,186 @@
+use record::Record;
+use record::SpillFile;
+use record::SpillWriter;
+use registry_args::RegistryArg;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::ClumperBe;
+use super::ClumperRegistrant;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+#[derive(Clone)]
+#[derive(Copy)]
+pub(crate) enum EvictMode {
+    Close,
+    Spill,
+}
+
+impl RegistryArg for EvictMode {
+    fn parse(arg: &str) -> ValidationResult<EvictMode> {
+        return match arg {
+            "close" => Result::Ok(EvictMode::Close),
+            "spill" => Result::Ok(EvictMode::Spill),
+            _ => ValidationError::message(format!("Invalid eviction mode (expected 'close' or 'spill'): {}", arg)),
+        };
+    }
+}
+
+#[derive(RegistryArgs)]
+pub struct Args {
+    key: Arc<str>,
+    max: usize,
+    mode: EvictMode,
+}
+
+pub type Impl = ClumperRegistrant<ImplBe>;
+
+pub struct ImplBe();
+
+impl ClumperBe for ImplBe {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["lru"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "bucket records by values of one key, keeping at most max buckets open; 'close' evicts the least recently used bucket (which may then be emitted more than once), 'spill' defers records for new values to disk and replays them in further passes";
+    }
+
+    fn stream(a: &Args, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let k = a.key.clone();
+        let max = std::cmp::max(1, a.max);
+
+        match a.mode {
+            EvictMode::Close => {
+                return stream::closures(
+                    LruState::default(),
+                    move |s, e, w| {
+                        let r = e.parse();
+
+                        let v = r.get_path(&k);
+
+                        let tick = s.tick;
+                        s.tick += 1;
+
+                        if let Some((tick0, substream)) = s.open.get_mut(&v) {
+                            s.order.remove(tick0);
+                            s.order.insert(tick, v);
+                            *tick0 = tick;
+
+                            // Disregard flow since one substream ending does
+                            // not mean we're done (e.g.  each substream could
+                            // be head -n 1).
+                            substream.write(Entry::Record(r), w);
+
+                            return true;
+                        }
+
+                        if s.open.len() >= max {
+                            let tick0 = *s.order.keys().next().unwrap();
+                            let v0 = s.order.remove(&tick0).unwrap();
+                            let (_tick0, substream) = s.open.remove(&v0).unwrap();
+                            substream.close(w);
+                            s.evictions += 1;
+                        }
+
+                        let mut substream = bsw(vec![(k.clone(), v.clone())]);
+                        substream.write(Entry::Record(r), w);
+                        s.order.insert(tick, v.clone());
+                        s.open.insert(v, (tick, substream));
+
+                        return true;
+                    },
+                    |mut s, w| {
+                        for (_tick, v) in s.order {
+                            let (_tick, substream) = s.open.remove(&v).unwrap();
+                            substream.close(w);
+                        }
+                        if s.evictions > 0 {
+                            eprintln!("lru clumper: evicted {} buckets early", s.evictions);
+                        }
+                    },
+                );
+            }
+            EvictMode::Spill => {
+                return stream::closures(
+                    SpillState {
+                        k: k,
+                        max: max,
+                        bsw: bsw,
+                        open: HashMap::new(),
+                        spill: None,
+                        spilled: 0,
+                    },
+                    |s, e, w| {
+                        s.add(e.parse(), w);
+                        return true;
+                    },
+                    |mut s, w| {
+                        let mut passes = 1;
+                        let mut spilled = 0;
+                        loop {
+                            for (_v, substream) in s.open.drain() {
+                                substream.close(w);
+                            }
+                            let (f, fw) = match s.spill.take() {
+                                Some(p) => p,
+                                None => break,
+                            };
+                            fw.finish();
+                            spilled += s.spilled;
+                            s.spilled = 0;
+                            passes += 1;
+                            for r in f.read() {
+                                s.add(r, w);
+                            }
+                        }
+                        if passes > 1 {
+                            eprintln!("lru clumper: spilled {} records over {} passes", spilled, passes);
+                        }
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct LruState {
+    tick: u64,
+    open: HashMap<Record, (u64, Stream)>,
+    order: BTreeMap<u64, Record>,
+    evictions: usize,
+}
+
+struct SpillState {
+    k: Arc<str>,
+    max: usize,
+    bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>,
+    open: HashMap<Record, Stream>,
+    spill: Option<(SpillFile, SpillWriter)>,
+    spilled: usize,
+}
+
+impl SpillState {
+    fn add(&mut self, r: Record, w: &mut FnMut(Entry) -> bool) {
+        let v = r.get_path(&self.k);
+
+        if !self.open.contains_key(&v) {
+            if self.open.len() >= self.max {
+                self.spill.get_or_insert_with(SpillFile::new).1.write(&r);
+                self.spilled += 1;
+                return;
+            }
+            self.open.insert(v.clone(), (self.bsw)(vec![(self.k.clone(), v.clone())]));
+        }
+
+        // Disregard flow since one substream ending does not mean we're done
+        // (e.g.  each substream could be head -n 1).
+        self.open.get_mut(&v).unwrap().write(Entry::Record(r), w);
+    }
+}
@@ -1,12 +1,43 @@
+use misc::seed::SeedSource;
 use record::Record;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
 use std::sync::Arc;
 use stream::Stream;
 use super::ClumperBe;
 use super::ClumperRegistrant;
+use validates::ValidationError;
+use validates::ValidationResult;
 
-#[derive(RegistryArgs)]
 pub struct Args {
     count: usize,
+    seed: Option<u64>,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",count[,seed]";
+    }
+
+    fn argct() -> usize {
+        return 1;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let seed = match args.len() {
+            1 => None,
+            2 => Some(<u64 as RegistryArg>::parse(args[1]).map_err(|e| e.label("While parsing seed"))?),
+            _ => return ValidationError::message("Too many arguments for round-robin"),
+        };
+        return Result::Ok(Args {
+            count: <usize as RegistryArg>::parse(args[0]).map_err(|e| e.label("While parsing count"))?,
+            seed: seed,
+        });
+    }
 }
 
 pub type Impl = ClumperRegistrant<ImplBe>;
@@ -21,15 +52,20 @@ impl ClumperBe for ImplBe {
     }
 
     fn help_msg() -> &'static str {
-        return "bucket records rotating between a specified number of buckets";
+        return "bucket records rotating between a specified number of buckets (starting from a random one if given a seed)";
     }
 
     fn stream(a: &Args, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
         let n = a.count;
         let substreams: Vec<_> = (0..n).map(|_| bsw(vec![])).collect();
+        // Only an explicit seed picks a random starting bucket.
+        let start = match a.seed {
+            Some(seed) => SeedSource::new(Some(seed)).gen_range(0, n),
+            None => 0,
+        };
 
         return stream::closures(
-            (substreams, 0),
+            (substreams, start),
             |s, e, w| {
                 let i = s.1;
                 let i = (i + 1) % s.0.len();
@@ -0,0 +1,80 @@
+use record::Record;
+use record::RecordTrait;
+use registry_args::DurationArg;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::ClumperBe;
+use super::ClumperRegistrant;
+use super::time::timestamp_record;
+
+#[derive(RegistryArgs)]
+pub struct Args {
+    key: Arc<str>,
+    gap: DurationArg,
+}
+
+struct Session {
+    end: f64,
+    substream: Stream,
+}
+
+pub type Impl = ClumperRegistrant<ImplBe>;
+
+pub struct ImplBe();
+
+impl ClumperBe for ImplBe {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["session"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "bucket records into sessions of a timestamp key, closing each after a gap of inactivity (e.g. session,ts,30m); buckets carry session_start";
+    }
+
+    fn stream(a: &Args, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let k = a.key.clone();
+        let gap = a.gap.0;
+
+        // Records go straight through, so the bucket only knows where its
+        // session started.
+        return stream::closures(
+            None,
+            move |s: &mut Option<Session>, e, w| {
+                let r = e.parse();
+
+                let ts = r.get_path(&k).coerce_timestamp();
+
+                let open = match s {
+                    Some(session) => ts - session.end <= gap,
+                    None => false,
+                };
+                if !open {
+                    let substream = bsw(vec![(Arc::from("session_start"), timestamp_record(ts))]);
+                    if let Some(session) = s.replace(Session {
+                        end: ts,
+                        substream: substream,
+                    }) {
+                        session.substream.close(w);
+                    }
+                }
+
+                let session = s.as_mut().unwrap();
+                session.end = session.end.max(ts);
+
+                // Again, substream ending does not concern us, we may need to
+                // truck on for later sessions.
+                session.substream.write(Entry::Record(r), w);
+
+                return true;
+            },
+            |s, w| {
+                if let Some(session) = s {
+                    session.substream.close(w);
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use record::Record;
+use record::RecordTrait;
+use registry_args::DurationArg;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::ClumperBe;
+use super::ClumperRegistrant;
+use super::time::close_complete;
+use super::time::timestamp_record;
+
+#[derive(RegistryArgs)]
+pub struct Args {
+    key: Arc<str>,
+    size: DurationArg,
+    step: DurationArg,
+}
+
+pub type Impl = ClumperRegistrant<ImplBe>;
+
+pub struct ImplBe();
+
+impl ClumperBe for ImplBe {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["sliding"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "bucket records into [overlapping] windows of a timestamp key of a given size starting every step (e.g. sliding,ts,1h,5m)";
+    }
+
+    fn stream(a: &Args, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let k = a.key.clone();
+        let size = a.size.0;
+        let step = a.step.0;
+
+        return stream::closures(
+            (BTreeMap::new(), std::f64::NEG_INFINITY),
+            move |s, e, w| {
+                let (windows, max_ts) = s;
+                let r = e.parse();
+
+                let ts = r.get_path(&k).coerce_timestamp();
+                let first = ((ts - size) / step).floor() as i64 + 1;
+                let last = (ts / step).floor() as i64;
+
+                for idx in first..=last {
+                    let (_end, substream) = windows.entry(idx).or_insert_with(|| {
+                        let start = (idx as f64) * step;
+                        let end = start + size;
+                        return (end, bsw(vec![(Arc::from("window_start"), timestamp_record(start)), (Arc::from("window_end"), timestamp_record(end))]));
+                    });
+
+                    // Disregard flow since one substream ending does
+                    // not mean we're done (e.g.  each substream could
+                    // be head -n 1).
+                    substream.write(Entry::Record(r.clone()), w);
+                }
+
+                if ts > *max_ts {
+                    *max_ts = ts;
+                    close_complete(windows, ts, w);
+                }
+
+                return true;
+            },
+            |s, w| {
+                for (_, (_end, substream)) in s.0.into_iter() {
+                    substream.close(w);
+                }
+            },
+        );
+    }
+}
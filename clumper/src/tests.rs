@@ -0,0 +1,310 @@
+use record::Record;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use stream::Entry;
+use super::BoxedClumper;
+
+fn clumper(spec: &str) -> BoxedClumper {
+    let mut parts = spec.split(',');
+    let name = parts.next().unwrap();
+    let args: Vec<_> = parts.collect();
+    return super::REGISTRY.find(name, &args).unwrap_or_else(|_| panic!("Bad clumper: {}", spec));
+}
+
+fn bucket_desc(bucket: &Vec<(Arc<str>, Record)>) -> String {
+    return bucket.iter().map(|(k, v)| format!("{}={}", k, v.deparse())).collect::<Vec<_>>().join(",");
+}
+
+// One output line per closed bucket: the bucket's fields, then the "i" of
+// each of its records.
+fn run(specs: &[&str], input: &[&str]) -> Vec<String> {
+    return run_cws(specs.iter().map(|spec| clumper(spec)).collect(), input);
+}
+
+fn run_cws(cws: Vec<BoxedClumper>, input: &[&str]) -> Vec<String> {
+    let mut s = super::stream(&cws, |bucket| {
+        let desc = bucket_desc(&bucket);
+        return stream::closures(
+            Vec::new(),
+            |is: &mut Vec<String>, e, _w| {
+                is.push(e.parse().get_path("i").deparse().to_string());
+                return true;
+            },
+            move |is, w| {
+                w(Entry::Line(Arc::from(format!("{} {}", desc, is.join(" ")))));
+            },
+        );
+    });
+    let mut out = Vec::new();
+    for (i, line) in input.iter().enumerate() {
+        let mut r = Entry::Line(Arc::from(*line)).parse();
+        r.set_path("i", Record::from(i as i64));
+        s.write(Entry::Record(r), &mut |e| {
+            out.push(e.deparse().to_string());
+            return true;
+        });
+    }
+    s.close(&mut |e| {
+        out.push(e.deparse().to_string());
+        return true;
+    });
+    return out;
+}
+
+fn ts(t: &str) -> String {
+    return format!("{{\"t\":{}}}", t);
+}
+
+fn tss(ts0: &[&str]) -> Vec<String> {
+    return ts0.iter().map(|t| ts(t)).collect();
+}
+
+fn strs(v: &[String]) -> Vec<&str> {
+    return v.iter().map(|s| s as &str).collect();
+}
+
+#[test]
+fn test_time() {
+    let input = tss(&["0", "59", "60", "30", "\"1970-01-01T00:03:00Z\"", "61"]);
+    assert_eq!(run(&["time,t,1m"], &strs(&input)), vec![
+        "window_start=0,window_end=60 0 1",
+        // Late for a closed window: a fresh bucket, closed with the next
+        // advance.
+        "window_start=0,window_end=60 3",
+        "window_start=60,window_end=120 2",
+        // Likewise, and the rest close in window order at the end.
+        "window_start=60,window_end=120 5",
+        "window_start=180,window_end=240 4",
+    ]);
+}
+
+#[test]
+fn test_sliding() {
+    let input = tss(&["0", "5", "12"]);
+    assert_eq!(run(&["sliding,t,10s,5s"], &strs(&input)), vec![
+        "window_start=-5,window_end=5 0",
+        "window_start=0,window_end=10 0 1",
+        "window_start=5,window_end=15 1 2",
+        "window_start=10,window_end=20 2",
+    ]);
+}
+
+#[test]
+fn test_session() {
+    let input = tss(&["0", "10", "25", "100", "95", "1000.5"]);
+    assert_eq!(run(&["session,t,20s"], &strs(&input)), vec![
+        "session_start=0 0 1 2",
+        "session_start=100 3 4",
+        "session_start=1000.5 5",
+    ]);
+}
+
+#[test]
+fn test_session_streams() {
+    // Records reach the bucket as they arrive, not when the session closes.
+    let cws = vec![clumper("session,t,1h")];
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen1 = seen.clone();
+    let mut s = super::stream(&cws, move |_bucket| {
+        let seen1 = seen1.clone();
+        return stream::closures(
+            (),
+            move |_s, e, _w| {
+                seen1.borrow_mut().push(e.parse().get_path("t").deparse());
+                return true;
+            },
+            |_s, _w| {
+            },
+        );
+    });
+    for t in &["0", "10", "20"] {
+        s.write(Entry::Line(Arc::from(ts(t))), &mut |_e| true);
+        assert_eq!(seen.borrow().last(), Some(&t.to_string()));
+    }
+}
+
+#[test]
+fn test_duration_args() {
+    assert!(super::REGISTRY.find("time", &["t", "0s"]).is_err());
+    assert!(super::REGISTRY.find("time", &["t", "5x"]).is_err());
+    assert!(super::REGISTRY.find("sliding", &["t", "1h"]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "unparseable")]
+fn test_time_rejects_nan() {
+    run(&["time,t,1m"], &[r#"{"t":"NaN"}"#]);
+}
+
+fn ks(ks0: &[&str]) -> Vec<String> {
+    return ks0.iter().map(|k| format!("{{\"k\":{}}}", k)).collect();
+}
+
+#[test]
+fn test_adjacent() {
+    let input = ks(&["1", "1", "2", "1", "\"1\"", "\"1\""]);
+    assert_eq!(run(&["adjacent,k"], &strs(&input)), vec![
+        "k=1 0 1",
+        "k=2 2",
+        "k=1 3",
+        "k=\"1\" 4 5",
+    ]);
+    assert_eq!(run(&["run,k"], &[]), Vec::<String>::new());
+}
+
+#[test]
+fn test_lru_close() {
+    let input = ks(&["1", "2", "1", "3", "2", "1"]);
+    assert_eq!(run(&["lru,k,2,close"], &strs(&input)), vec![
+        // 2 is least recently used when 3 arrives, and 1 when 2 returns.
+        "k=2 1",
+        "k=1 0 2",
+        "k=3 3",
+        "k=2 4",
+        "k=1 5",
+    ]);
+    assert_eq!(run(&["lru,k,10,close"], &strs(&input)), vec![
+        "k=3 3",
+        "k=2 1 4",
+        "k=1 0 2 5",
+    ]);
+}
+
+#[test]
+fn test_lru_spill() {
+    let input = ks(&["1", "2", "1", "3", "2", "4", "3", "1"]);
+    let mut out = run(&["lru,k,2,spill"], &strs(&input));
+    // Open buckets close in hash order, but each value gets exactly one
+    // bucket with all of its records, in order.
+    out.sort();
+    assert_eq!(out, vec![
+        "k=1 0 2 7",
+        "k=2 1 4",
+        "k=3 3 6",
+        "k=4 5",
+    ]);
+}
+
+#[test]
+fn test_lru_args() {
+    assert!(super::REGISTRY.find("lru", &["k", "2", "drop"]).is_err());
+    assert!(super::REGISTRY.find("lru", &["k", "x", "close"]).is_err());
+}
+
+fn grouping_sets_input() -> Vec<&'static str> {
+    return vec![r#"{"a":1,"b":"x"}"#, r#"{"a":1,"b":"y"}"#, r#"{"a":2,"b":"x"}"#];
+}
+
+#[test]
+fn test_cube() {
+    let keys = vec![Arc::from("a"), Arc::from("b")];
+    let mut out = run_cws(vec![super::grouping_sets::cube(keys)], &grouping_sets_input());
+    out.sort();
+    assert_eq!(out, vec![
+        "a=\"ALL\",b=\"ALL\" 0 1 2",
+        "a=\"ALL\",b=\"x\" 0 2",
+        "a=\"ALL\",b=\"y\" 1",
+        "a=1,b=\"ALL\" 0 1",
+        "a=1,b=\"x\" 0",
+        "a=1,b=\"y\" 1",
+        "a=2,b=\"ALL\" 2",
+        "a=2,b=\"x\" 2",
+    ]);
+}
+
+#[test]
+fn test_rollup() {
+    let keys = vec![Arc::from("a"), Arc::from("b")];
+    let mut out = run_cws(vec![super::grouping_sets::rollup(keys)], &grouping_sets_input());
+    out.sort();
+    assert_eq!(out, vec![
+        "a=\"ALL\",b=\"ALL\" 0 1 2",
+        "a=1,b=\"ALL\" 0 1",
+        "a=1,b=\"x\" 0",
+        "a=1,b=\"y\" 1",
+        "a=2,b=\"ALL\" 2",
+        "a=2,b=\"x\" 2",
+    ]);
+}
+
+#[test]
+fn test_rollup_nested() {
+    // Grouping sets compose with other clumpers like any clumper.
+    let cws = vec![clumper("adjacent,b"), super::grouping_sets::rollup(vec![Arc::from("a")])];
+    let mut out = run_cws(cws, &grouping_sets_input());
+    out.sort();
+    assert_eq!(out, vec![
+        "b=\"x\",a=\"ALL\" 0",
+        "b=\"x\",a=\"ALL\" 2",
+        "b=\"x\",a=1 0",
+        "b=\"x\",a=2 2",
+        "b=\"y\",a=\"ALL\" 1",
+        "b=\"y\",a=1 1",
+    ]);
+}
+
+fn shards(out: &[String]) -> Vec<(i64, Vec<usize>)> {
+    let mut ret: Vec<_> = out.iter().map(|line| {
+        let mut parts = line.split(' ');
+        let shard = parts.next().unwrap().trim_start_matches("shard=").parse().unwrap();
+        return (shard, parts.map(|i| i.parse().unwrap()).collect());
+    }).collect();
+    ret.sort();
+    return ret;
+}
+
+#[test]
+fn test_hash() {
+    let input: Vec<_> = (0..40).map(|i| format!("{{\"a\":{},\"b\":{}}}", i % 5, i % 2)).collect();
+    for spec in &["hash,3,a", "hash,3,a,b"] {
+        let out = shards(&run(&[spec], &strs(&input)));
+        // Every record lands in exactly one of the shards, in order ...
+        let mut all: Vec<_> = out.iter().flat_map(|(_shard, is)| is.iter().cloned()).collect();
+        all.sort();
+        assert_eq!(all, (0..40).collect::<Vec<_>>());
+        for (shard, is) in &out {
+            assert!(*shard >= 0 && *shard < 3);
+            assert!(is.windows(2).all(|w| w[0] < w[1]));
+        }
+        // ... and records with the same keys share one.
+        for i in 0..40 {
+            let (shard, _is) = out.iter().find(|(_shard, is)| is.contains(&i)).unwrap();
+            let (shard10, _is) = out.iter().find(|(_shard, is)| is.contains(&(i % 10))).unwrap();
+            assert_eq!(shard, shard10);
+        }
+    }
+}
+
+#[test]
+fn test_hash_keys() {
+    let input = [r#"{"a":"x","b":"y"}"#];
+    let shard = |spec: &str| shards(&run(&[spec], &input))[0].0 as u64;
+    let r = Record::parse(input[0]);
+    assert_eq!(shard("hash,1000,a"), r.get_path("a").stable_hash() % 1000);
+    assert_eq!(shard("hash,1000,a,b"), Record::parse(r#"["x","y"]"#).stable_hash() % 1000);
+    assert!(super::REGISTRY.find("hash", &["3"]).is_err());
+    assert!(super::REGISTRY.find("hash", &["x", "a"]).is_err());
+}
+
+#[test]
+fn test_round_robin() {
+    let input: Vec<_> = (0..7).map(|_| "{}").collect();
+    let unseeded = vec![" 2 5", " 0 3 6", " 1 4"];
+    assert_eq!(run(&["rr,3"], &input), unseeded);
+    // A global seed doesn't move the unseeded start.
+    misc::seed::set_global_seed(5);
+    assert_eq!(run(&["round-robin,3"], &input), unseeded);
+
+    // A seed only moves the start, the same way each time.
+    assert_eq!(run(&["rr,3,17"], &input), run(&["rr,3,17"], &input));
+    let rotations: Vec<_> = (0..3).map(|i| {
+        let mut v = unseeded.clone();
+        v.rotate_left(i);
+        return v;
+    }).collect();
+    for seed in 0..20 {
+        let out = run(&[&format!("rr,3,{}", seed)], &input);
+        assert!(rotations.iter().any(|v| v == &out), "{:?}", out);
+    }
+}
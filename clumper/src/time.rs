@@ -0,0 +1,91 @@
+use record::Record;
+use record::RecordTrait;
+use registry_args::DurationArg;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::ClumperBe;
+use super::ClumperRegistrant;
+
+pub(crate) fn timestamp_record(t: f64) -> Record {
+    if t.fract() == 0.0 {
+        return Record::from(t as i64);
+    }
+    return Record::from(t);
+}
+
+// Windows that end at or before the latest timestamp seen are complete and
+// get closed.  Records arriving later than that for such a window start a
+// fresh bucket for it.
+pub(crate) fn close_complete(s: &mut BTreeMap<i64, (f64, Stream)>, max_ts: f64, w: &mut FnMut(Entry) -> bool) {
+    loop {
+        let k = match s.iter().next() {
+            Some((k, (end, _substream))) if *end <= max_ts => *k,
+            _ => return,
+        };
+        let (_end, substream) = s.remove(&k).unwrap();
+        substream.close(w);
+    }
+}
+
+#[derive(RegistryArgs)]
+pub struct Args {
+    key: Arc<str>,
+    size: DurationArg,
+}
+
+pub type Impl = ClumperRegistrant<ImplBe>;
+
+pub struct ImplBe();
+
+impl ClumperBe for ImplBe {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["time", "tumbling"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "bucket records into consecutive, non-overlapping windows of a timestamp key (e.g. time,ts,5m)";
+    }
+
+    fn stream(a: &Args, bsw: Box<Fn(Vec<(Arc<str>, Record)>) -> Stream>) -> Stream {
+        let k = a.key.clone();
+        let size = a.size.0;
+
+        return stream::closures(
+            (BTreeMap::new(), std::f64::NEG_INFINITY),
+            move |s, e, w| {
+                let (windows, max_ts) = s;
+                let r = e.parse();
+
+                let ts = r.get_path(&k).coerce_timestamp();
+                let idx = (ts / size).floor() as i64;
+
+                let (_end, substream) = windows.entry(idx).or_insert_with(|| {
+                    let start = (idx as f64) * size;
+                    let end = start + size;
+                    return (end, bsw(vec![(Arc::from("window_start"), timestamp_record(start)), (Arc::from("window_end"), timestamp_record(end))]));
+                });
+
+                // Disregard flow since one substream ending does
+                // not mean we're done (e.g.  each substream could
+                // be head -n 1).
+                substream.write(Entry::Record(r), w);
+
+                if ts > *max_ts {
+                    *max_ts = ts;
+                    close_complete(windows, ts, w);
+                }
+
+                return true;
+            },
+            |s, w| {
+                for (_, (_end, substream)) in s.0.into_iter() {
+                    substream.close(w);
+                }
+            },
+        );
+    }
+}
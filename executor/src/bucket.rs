@@ -0,0 +1,20 @@
+use record::Record;
+use record::RecordTrait;
+use std::cell::RefCell;
+
+// The bucket of the innermost enclosing multiplex, made available to code
+// while its sub-operation's streams are being built.
+thread_local! {
+    static BUCKET: RefCell<Record> = RefCell::new(Record::empty_hash());
+}
+
+pub fn current_bucket() -> Record {
+    return BUCKET.with(|b| b.borrow().clone());
+}
+
+pub fn with_bucket<R, F: FnOnce() -> R>(bucket: Record, f: F) -> R {
+    let prev = BUCKET.with(|b| b.replace(bucket));
+    let ret = f();
+    BUCKET.with(|b| b.replace(prev));
+    return ret;
+}
@@ -10,6 +10,13 @@ extern crate registry_args;
 extern crate rlua;
 extern crate validates;
 
+mod bucket;
+pub use self::bucket::current_bucket;
+pub use self::bucket::with_bucket;
+mod local;
+pub use self::local::CodeArg;
+pub use self::local::LocalStream;
+
 use record::Record;
 use registry::Registrant;
 use registry::args::ZeroRegistryArgs;
@@ -97,3 +104,6 @@ impl<B: ExecutorBe + 'static> Executor2Inbox for Executor2InboxImpl<B> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,110 @@
+use record::Record;
+use registry_args::RegistryArg;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use super::BoxedExecutor2;
+use validates::ValidationResult;
+
+// Executor streams can't cross threads, so things that must (e.g.
+// aggregators) hold this handle instead and the stream itself lives in a
+// thread local, built on first use.  Only the owning thread can drop a
+// stream, so a handle dropped elsewhere leaves its id in the owner's inbox,
+// which the owner clears out the next time it calls any stream (or when it
+// exits).
+thread_local! {
+    static STREAMS: RefCell<HashMap<usize, Box<FnMut(Record) -> Record>>> = RefCell::new(HashMap::new());
+    static INBOX: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub struct LocalStream {
+    id: usize,
+    code: BoxedExecutor2,
+    ret: bool,
+    // The stream is built lazily, so it's built under the bucket that was
+    // current when the handle was made (or cloned).
+    bucket: Record,
+    // Inboxes of the threads holding a stream for this handle.
+    owners: Mutex<Vec<Weak<Mutex<Vec<usize>>>>>,
+}
+
+impl LocalStream {
+    pub fn new(code: BoxedExecutor2, ret: bool) -> LocalStream {
+        return LocalStream {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            code: code,
+            ret: ret,
+            bucket: super::current_bucket(),
+            owners: Mutex::new(Vec::new()),
+        };
+    }
+
+    pub fn call(&self, r: Record) -> Record {
+        let dropped = INBOX.with(|inbox| std::mem::replace(&mut *inbox.lock().unwrap(), Vec::new()));
+        // Collected so they're dropped outside the borrow.
+        let _stale: Vec<_> = STREAMS.with(|ss| dropped.iter().map(|id| ss.borrow_mut().remove(id)).collect());
+        let mut f = STREAMS.with(|ss| ss.borrow_mut().remove(&self.id)).unwrap_or_else(|| {
+            self.owners.lock().unwrap().push(INBOX.with(Arc::downgrade));
+            return super::with_bucket(self.bucket.clone(), || self.code.stream(self.ret));
+        });
+        let ret = f(r);
+        STREAMS.with(|ss| ss.borrow_mut().insert(self.id, f));
+        return ret;
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn live_streams() -> usize {
+    return STREAMS.with(|ss| ss.borrow().len());
+}
+
+// A clone starts with a fresh stream, under the current bucket.
+impl Clone for LocalStream {
+    fn clone(&self) -> LocalStream {
+        return LocalStream::new(self.code.clone(), self.ret);
+    }
+}
+
+impl Drop for LocalStream {
+    fn drop(&mut self) {
+        let id = self.id;
+        let here = INBOX.try_with(Arc::downgrade).ok();
+        for owner in self.owners.get_mut().unwrap().drain(..) {
+            if here.as_ref().map(|here| here.ptr_eq(&owner)).unwrap_or(false) {
+                let _ = STREAMS.try_with(|ss| ss.borrow_mut().remove(&id));
+                continue;
+            }
+            if let Some(inbox) = owner.upgrade() {
+                inbox.lock().unwrap().push(id);
+            }
+        }
+    }
+}
+
+// Code for an executor engine, r4l unless prefixed with an engine name (e.g.
+// "lua:r.x > 3").
+#[derive(Clone)]
+pub struct CodeArg(BoxedExecutor2);
+
+impl RegistryArg for CodeArg {
+    fn parse(arg: &str) -> ValidationResult<CodeArg> {
+        let (engine, code) = match arg.find(':') {
+            Some(i) if super::REGISTRY.find(&arg[0..i], &[]).is_ok() => (&arg[0..i], &arg[(i + 1)..]),
+            _ => ("r4l", arg),
+        };
+        let executor = super::REGISTRY.find(engine, &[])?;
+        return Result::Ok(CodeArg(executor.parse(code)?));
+    }
+}
+
+impl CodeArg {
+    pub fn stream(&self, ret: bool) -> LocalStream {
+        return LocalStream::new(self.0.clone(), ret);
+    }
+}
@@ -149,6 +149,8 @@ impl ExecutorBe for ImplBe {
             return MRecordHolder(MRecord::from_vec(t.sequence_values().map(|v| from_lua(lua, v.unwrap())).collect())).to_lua(lua);
         }).unwrap()).unwrap();
 
+        lua.globals().set("BUCKET", MRecordHolder(MRecord::wrap(super::current_bucket()))).unwrap();
+
         // Your "main" function.  We hold a RegistryKey since basically
         // anything else is lifetime tied to lua object and we therefore simply
         // can't keep them.
@@ -215,6 +215,7 @@ impl ExecutorBe for ImplBe {
     fn stream(code: &Code, ret: bool) -> Box<FnMut(Record) -> Record> {
         let e = code.0.clone();
         let mut st = State::default();
+        st.vars.insert(Arc::from("BUCKET"), MRecord::wrap(super::current_bucket()));
         return Box::new(move |r| {
             st.vars.insert(Arc::from("r"), MRecord::wrap(r));
             let rr = st.eval(&e);
@@ -0,0 +1,47 @@
+use record::Record;
+use registry_args::RegistryArg;
+use std::thread;
+use super::CodeArg;
+use super::local::live_streams;
+
+fn counter() -> CodeArg {
+    return CodeArg::parse("lua:n = (n or 0) + 1; return n").unwrap_or_else(|_| panic!());
+}
+
+#[test]
+fn test_local_stream_state() {
+    let ls = counter().stream(true);
+    assert_eq!(ls.call(Record::parse("{}")).deparse(), "1");
+    assert_eq!(ls.call(Record::parse("{}")).deparse(), "2");
+    // Clones and other threads start over.
+    let ls2 = ls.clone();
+    assert_eq!(ls2.call(Record::parse("{}")).deparse(), "1");
+    let ls = thread::spawn(move || {
+        assert_eq!(ls.call(Record::parse("{}")).deparse(), "1");
+        return ls;
+    }).join().unwrap();
+    assert_eq!(ls.call(Record::parse("{}")).deparse(), "3");
+}
+
+#[test]
+fn test_local_stream_drop() {
+    // Dropped on its own thread: gone at once.
+    let before = live_streams();
+    let ls = counter().stream(true);
+    ls.call(Record::parse("{}"));
+    assert_eq!(live_streams(), before + 1);
+    drop(ls);
+    assert_eq!(live_streams(), before);
+
+    // Dropped elsewhere: gone at this thread's next call.
+    let ls = counter().stream(true);
+    ls.call(Record::parse("{}"));
+    thread::spawn(move || {
+        ls.call(Record::parse("{}"));
+        drop(ls);
+    }).join().unwrap();
+    assert_eq!(live_streams(), before + 1);
+    let ls2 = counter().stream(true);
+    ls2.call(Record::parse("{}"));
+    assert_eq!(live_streams(), before + 1);
+}
@@ -1,3 +1,10 @@
+#[macro_use]
+extern crate lazy_static;
+extern crate rand;
+extern crate rand_chacha;
+
+pub mod seed;
+
 use std::rc::Rc;
 
 #[derive(Clone)]
@@ -0,0 +1,53 @@
+use rand::Rng;
+use rand::SeedableRng;
+use rand::distributions::Distribution;
+use rand::distributions::Standard;
+use rand_chacha::ChaChaRng;
+use std::sync::Mutex;
+
+pub type SeededRng = ChaChaRng;
+
+// Once seeded, every random component draws its own seed from this in
+// construction order so whole runs are reproducible.
+lazy_static! {
+    static ref GLOBAL: Mutex<Option<SeededRng>> = Mutex::new(None);
+}
+
+pub fn set_global_seed(seed: u64) {
+    *GLOBAL.lock().unwrap() = Some(SeededRng::seed_from_u64(seed));
+}
+
+fn next_seed() -> u64 {
+    return match &mut *GLOBAL.lock().unwrap() {
+        Some(rng) => rng.gen(),
+        None => rand::thread_rng().gen(),
+    };
+}
+
+// A fresh generator, seeded from the global seed if there is one.
+pub fn rng() -> SeededRng {
+    return SeededRng::seed_from_u64(next_seed());
+}
+
+// A random component's source of randomness, from its own seed if it was
+// given one.
+pub struct SeedSource(Mutex<SeededRng>);
+
+impl SeedSource {
+    pub fn new(seed: Option<u64>) -> SeedSource {
+        return SeedSource(Mutex::new(SeededRng::seed_from_u64(seed.unwrap_or_else(next_seed))));
+    }
+
+    pub fn gen<T>(&self) -> T where Standard: Distribution<T> {
+        return self.0.lock().unwrap().gen();
+    }
+
+    pub fn gen_range(&self, lo: usize, hi: usize) -> usize {
+        return self.0.lock().unwrap().gen_range(lo, hi);
+    }
+
+    // An independent generator, e.g. for one bucket of many.
+    pub fn rng(&self) -> SeededRng {
+        return SeededRng::seed_from_u64(self.gen());
+    }
+}
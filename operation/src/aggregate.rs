@@ -12,14 +12,50 @@ use super::OperationBe2;
 use super::OperationBeForBe2;
 use super::OperationRegistrant;
 use super::TwoRecordUnionOption;
+use validates::Validates;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+#[derive(Default)]
+struct AggsOption {
+    aggs: UnvalidatedOption<Vec<(String, BoxedAggregator)>>,
+    merge_partial: BooleanOption,
+}
+
+#[derive(Clone)]
+struct Aggs {
+    aggs: Vec<(String, BoxedAggregator)>,
+    merge_partial: bool,
+}
+
+impl Validates for AggsOption {
+    type Target = Aggs;
+
+    fn validate(self) -> ValidationResult<Aggs> {
+        let aggs = self.aggs.validate()?;
+        let merge_partial = self.merge_partial.validate()?;
+        if merge_partial {
+            for (label, agg) in aggs.iter() {
+                if !agg.mergeable() {
+                    return ValidationError::message(format!("Aggregator {} cannot merge partial states (eval needs merge code for --merge-partial)", label));
+                }
+            }
+        }
+        return Result::Ok(Aggs {
+            aggs: aggs,
+            merge_partial: merge_partial,
+        });
+    }
+}
 
 #[derive(Default)]
 #[derive(Validates)]
 pub struct Options {
-    aggs: UnvalidatedOption<Vec<(String, BoxedAggregator)>>,
+    aggs: AggsOption,
     tru: TwoRecordUnionOption,
     incremental: BooleanOption,
     no_bucket: BooleanOption,
+    emit_partial: BooleanOption,
 }
 
 pub(crate) type Impl = OperationRegistrant<ImplBe>;
@@ -32,14 +68,18 @@ impl Optionsable for ImplBe2 {
     type Options = Options;
 
     fn options(opt: &mut OptionsPile<Options>) {
-        opt.add_sub(|p| &mut p.aggs.0, aggregator::REGISTRY.labelled_single_options(&["a", "agg", "aggregator"], "aggregators to compute"));
-        opt.add_sub(|p| &mut p.aggs.0, aggregator::REGISTRY.labelled_multiple_options(&["a", "agg", "aggregator"]));
+        opt.add_sub(|p| &mut p.aggs.aggs.0, aggregator::REGISTRY.labelled_single_options(&["a", "agg", "aggregator"], "aggregators to compute"));
+        opt.add_sub(|p| &mut p.aggs.aggs.0, aggregator::REGISTRY.labelled_multiple_options(&["a", "agg", "aggregator"]));
         opt.add(aggregator::REGISTRY.help_options("aggregator"));
         opt.add_sub(|p| &mut p.tru, TwoRecordUnionOption::new_options());
         opt.match_zero(&["incremental"], |p| p.incremental.set(), "Output one record after each input (instead of one at end)");
         opt.match_zero(&["no-incremental"], |p| p.incremental.clear(), "(default)");
         opt.match_zero(&["bucket"], |p| p.no_bucket.clear(), "(default)");
         opt.match_zero(&["no-bucket"], |p| p.no_bucket.set(), "Keep input records in output records");
+        opt.match_zero(&["emit-partial"], |p| p.emit_partial.set(), "Output mergeable partial states instead of final values");
+        opt.match_zero(&["no-emit-partial"], |p| p.emit_partial.clear(), "(default)");
+        opt.match_zero(&["merge-partial"], |p| p.aggs.merge_partial.set(), "Treat input as partial states (from --emit-partial with the same aggregators) to merge");
+        opt.match_zero(&["no-merge-partial"], |p| p.aggs.merge_partial.clear(), "(default)");
     }
 }
 
@@ -58,10 +98,10 @@ impl OperationBe2 for ImplBe2 {
             aggs: Vec<(String, BoxedAggregator)>,
             recs: Vec<Record>,
         }
-        fn aggregate_record(aggs: Vec<(String, BoxedAggregator)>) -> Record {
+        fn aggregate_record(aggs: Vec<(String, BoxedAggregator)>, partial: bool) -> Record {
             let mut rhs = Record::empty_hash();
             for (label, state) in aggs.clone().into_iter() {
-                rhs.set_path(&label, state.finish());
+                rhs.set_path(&label, if partial { state.save() } else { state.finish() });
             }
             return rhs;
         }
@@ -69,22 +109,27 @@ impl OperationBe2 for ImplBe2 {
         return stream::closures(
             State {
                 o: o.clone(),
-                aggs: o.aggs.clone(),
+                aggs: o.aggs.aggs.clone(),
                 recs: Vec::new(),
             },
             |s, e, w| {
                 let r = e.parse();
 
-                for (_, ref mut state) in s.aggs.iter_mut() {
-                    state.add(r.clone());
+                for (label, ref mut state) in s.aggs.iter_mut() {
+                    if s.o.aggs.merge_partial {
+                        state.merge(r.get_path(label));
+                    }
+                    else {
+                        state.add(r.clone());
+                    }
                 }
 
                 if s.o.incremental {
                     if s.o.no_bucket {
-                        return w(Entry::Record(s.o.tru.union(r, aggregate_record(s.aggs.clone()))));
+                        return w(Entry::Record(s.o.tru.union(r, aggregate_record(s.aggs.clone(), s.o.emit_partial))));
                     }
 
-                    return w(Entry::Record(s.o.tru.union_maybe(None, Some(aggregate_record(s.aggs.clone())))));
+                    return w(Entry::Record(s.o.tru.union_maybe(None, Some(aggregate_record(s.aggs.clone(), s.o.emit_partial)))));
                 }
 
                 if s.o.no_bucket {
@@ -98,7 +143,7 @@ impl OperationBe2 for ImplBe2 {
                     return;
                 }
 
-                let rhs = aggregate_record(s.aggs);
+                let rhs = aggregate_record(s.aggs, s.o.emit_partial);
 
                 if !s.o.no_bucket {
                     w(Entry::Record(s.o.tru.union_maybe(None, Some(rhs))));
@@ -0,0 +1,258 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::StringVecOption;
+use opts::vals::UnvalidatedOption;
+use record::Record;
+use record::RecordTrait;
+use registry::Registrant;
+use sorts::SimpleSortArgs;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+use super::SortOptions;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+#[derive(Clone)]
+enum Annotation {
+    RowNumber(String),
+    Rank(String),
+    DenseRank(String),
+    PercentRank(String),
+    CumCount(String),
+    CumSum(String, String),
+    Percent(String, String),
+    ZScore(String, String),
+    Lag(String, String),
+    Lead(String, String),
+}
+
+fn parse_labelled(a: &str) -> ValidationResult<(String, String)> {
+    return match a.find('=') {
+        Some(i) => Result::Ok((a[0..i].to_string(), a[(i + 1)..].to_string())),
+        None => ValidationError::message(format!("Expected OUTPUT=KEY: {}", a)),
+    };
+}
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    keys: StringVecOption,
+    sorts: SortOptions,
+    annotations: UnvalidatedOption<Vec<Annotation>>,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.match_single(&["k", "key"], |p, a| p.keys.push_split(a), "keys to partition by");
+        opt.add_sub(|p| &mut p.sorts, SortOptions::new_options(&["s", "sort"], "sorts to order each partition by, as name,arg,... or, for sorts taking a single arg, name:arg (default: input order)"));
+        opt.add(SortOptions::help_options());
+        opt.match_single(&["l", "lex", "lexical"], |p, a| {
+            for a in a.split(',') {
+                p.sorts.push(sorts::lexical::Impl::init(SimpleSortArgs::new(a)?));
+            }
+            return Result::Ok(());
+        }, "keys to order by lexically, prefix with minus to order descending, suffix with :nullsfirst or :nullslast to place nulls (default: first ascending, last descending)");
+        opt.match_single(&["n", "num", "numeric"], |p, a| {
+            for a in a.split(',') {
+                p.sorts.push(sorts::numeric::Impl::init(SimpleSortArgs::new(a)?));
+            }
+            return Result::Ok(());
+        }, "keys to order by numerically, prefix with minus to order descending, suffix with :nullsfirst or :nullslast to place nulls (default: first ascending, last descending)");
+        opt.match_single(&["row-number"], |p, a| {
+            (p.annotations.0).push(Annotation::RowNumber(a.to_string()));
+            return Result::Ok(());
+        }, "output key for 1-based position within partition");
+        opt.match_single(&["rank"], |p, a| {
+            (p.annotations.0).push(Annotation::Rank(a.to_string()));
+            return Result::Ok(());
+        }, "output key for rank within partition (ties share a rank, leaving gaps)");
+        opt.match_single(&["dense-rank"], |p, a| {
+            (p.annotations.0).push(Annotation::DenseRank(a.to_string()));
+            return Result::Ok(());
+        }, "output key for rank within partition (ties share a rank, no gaps)");
+        opt.match_single(&["percent-rank"], |p, a| {
+            (p.annotations.0).push(Annotation::PercentRank(a.to_string()));
+            return Result::Ok(());
+        }, "output key for percent of the rest of the partition ranked below (100 * (rank - 1) / (n - 1), 0 if alone)");
+        opt.match_single(&["cumcount"], |p, a| {
+            (p.annotations.0).push(Annotation::CumCount(a.to_string()));
+            return Result::Ok(());
+        }, "output key for number of records in partition ordered at or before this one (including ties)");
+        opt.match_single(&["cumsum"], |p, a| {
+            let (out, key) = parse_labelled(a)?;
+            (p.annotations.0).push(Annotation::CumSum(out, key));
+            return Result::Ok(());
+        }, "OUTPUT=KEY, running sum of key within partition");
+        opt.match_single(&["pct", "percent"], |p, a| {
+            let (out, key) = parse_labelled(a)?;
+            (p.annotations.0).push(Annotation::Percent(out, key));
+            return Result::Ok(());
+        }, "OUTPUT=KEY, key as a percent of its partition total");
+        opt.match_single(&["zscore"], |p, a| {
+            let (out, key) = parse_labelled(a)?;
+            (p.annotations.0).push(Annotation::ZScore(out, key));
+            return Result::Ok(());
+        }, "OUTPUT=KEY, standard score of key within partition");
+        opt.match_single(&["lag"], |p, a| {
+            let (out, key) = parse_labelled(a)?;
+            (p.annotations.0).push(Annotation::Lag(out, key));
+            return Result::Ok(());
+        }, "OUTPUT=KEY, key of the previous record in partition");
+        opt.match_single(&["lead"], |p, a| {
+            let (out, key) = parse_labelled(a)?;
+            (p.annotations.0).push(Annotation::Lead(out, key));
+            return Result::Ok(());
+        }, "OUTPUT=KEY, key of the next record in partition");
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["annotate"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "add window-function style fields (rank, running sums, lag/lead, ...) to records, buffering all input";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        struct State {
+            o: Arc<OptionsValidated>,
+            idxs: HashMap<Vec<Record>, usize>,
+            partitions: Vec<Vec<Record>>,
+        }
+
+        return stream::closures(
+            State {
+                o: o,
+                idxs: HashMap::new(),
+                partitions: Vec::new(),
+            },
+            |s, e, _w| {
+                let r = e.parse();
+                let k: Vec<_> = s.o.keys.iter().map(|k| r.get_path(k)).collect();
+                let partitions = &mut s.partitions;
+                let idx = *s.idxs.entry(k).or_insert_with(|| {
+                    partitions.push(Vec::new());
+                    return partitions.len() - 1;
+                });
+                partitions[idx].push(r);
+                return true;
+            },
+            |s, w| {
+                for rs in s.partitions {
+                    for r in annotate(&s.o, rs) {
+                        if !w(Entry::Record(r)) {
+                            return;
+                        }
+                    }
+                }
+            },
+        );
+    }
+}
+
+fn annotate(o: &OptionsValidated, rs: Vec<Record>) -> Vec<Record> {
+    let mut rs: Vec<_> = rs.into_iter().map(|r| (o.sorts.key(&r), r)).collect();
+    rs.sort_by(|(k1, _r1), (k2, _r2)| o.sorts.cmp_keys(k1, k2));
+    let n = rs.len();
+
+    // Ranks are by sort keys, so ties (and everything, if unsorted) share.
+    let mut ranks = Vec::with_capacity(n);
+    let mut dense_ranks = Vec::with_capacity(n);
+    for i in 0..n {
+        if i > 0 && o.sorts.cmp_keys(&rs[i - 1].0, &rs[i].0) == Ordering::Equal {
+            ranks.push(ranks[i - 1]);
+            dense_ranks.push(dense_ranks[i - 1]);
+        }
+        else {
+            ranks.push(i + 1);
+            dense_ranks.push(dense_ranks.last().map(|d| d + 1).unwrap_or(1));
+        }
+    }
+
+    let rs: Vec<_> = rs.into_iter().map(|(_k, r)| r).collect();
+    let mut outs = rs.clone();
+    for a in o.annotations.iter() {
+        match a {
+            Annotation::RowNumber(out) => {
+                for (i, r) in outs.iter_mut().enumerate() {
+                    r.set_path(out, Record::from((i + 1) as i64));
+                }
+            }
+            Annotation::Rank(out) => {
+                for (r, rank) in outs.iter_mut().zip(ranks.iter()) {
+                    r.set_path(out, Record::from(*rank as i64));
+                }
+            }
+            Annotation::DenseRank(out) => {
+                for (r, rank) in outs.iter_mut().zip(dense_ranks.iter()) {
+                    r.set_path(out, Record::from(*rank as i64));
+                }
+            }
+            Annotation::PercentRank(out) => {
+                for (r, rank) in outs.iter_mut().zip(ranks.iter()) {
+                    let pr = if n > 1 { 100.0 * (*rank - 1) as f64 / (n - 1) as f64 } else { 0.0 };
+                    r.set_path(out, Record::from(pr));
+                }
+            }
+            Annotation::CumCount(out) => {
+                let mut ct = n;
+                for i in (0..n).rev() {
+                    if i + 1 < n && ranks[i + 1] != ranks[i] {
+                        ct = i + 1;
+                    }
+                    outs[i].set_path(out, Record::from(ct as i64));
+                }
+            }
+            Annotation::CumSum(out, key) => {
+                let mut sum = 0.0;
+                for (r0, r) in rs.iter().zip(outs.iter_mut()) {
+                    sum += r0.get_path(key).coerce_f64();
+                    r.set_path(out, Record::from(sum));
+                }
+            }
+            Annotation::Percent(out, key) => {
+                let total: f64 = rs.iter().map(|r| r.get_path(key).coerce_f64()).sum();
+                for (r0, r) in rs.iter().zip(outs.iter_mut()) {
+                    r.set_path(out, Record::from(100.0 * r0.get_path(key).coerce_f64() / total));
+                }
+            }
+            Annotation::ZScore(out, key) => {
+                let vs: Vec<_> = rs.iter().map(|r| r.get_path(key).coerce_f64()).collect();
+                let mean = vs.iter().sum::<f64>() / n as f64;
+                let sd = (vs.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n as f64).sqrt();
+                for (v, r) in vs.iter().zip(outs.iter_mut()) {
+                    r.set_path(out, Record::from((v - mean) / sd));
+                }
+            }
+            Annotation::Lag(out, key) => {
+                for i in 0..n {
+                    let v = if i > 0 { rs[i - 1].get_path(key) } else { Record::null() };
+                    outs[i].set_path(out, v);
+                }
+            }
+            Annotation::Lead(out, key) => {
+                for i in 0..n {
+                    let v = if i + 1 < n { rs[i + 1].get_path(key) } else { Record::null() };
+                    outs[i].set_path(out, v);
+                }
+            }
+        }
+    }
+    return outs;
+}
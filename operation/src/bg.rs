@@ -3,6 +3,7 @@ use opts::parser::Optionsable;
 use std::sync::Arc;
 use std::thread;
 use stream::Stream;
+use super::BgopOptions;
 use super::OperationBe;
 use super::OperationRegistrant;
 use super::SubOperationOption;
@@ -10,6 +11,7 @@ use super::SubOperationOption;
 #[derive(Default)]
 #[derive(Validates)]
 pub struct Options {
+    bgop: BgopOptions,
     op: SubOperationOption,
 }
 
@@ -21,6 +23,7 @@ impl Optionsable for ImplBe {
     type Options = Options;
 
     fn options(opt: &mut OptionsPile<Options>) {
+        opt.add_sub(|p| &mut p.bgop, BgopOptions::new_options());
         opt.match_extra_hard(|p, a| p.op.push(a), "operation to run in the background");
     }
 }
@@ -39,10 +42,11 @@ impl OperationBe for ImplBe {
     }
 
     fn stream(o: Arc<OptionsValidated>) -> Stream {
-        let (fe, rbe, wbe) = bgop::new();
+        let (fe, mut rbe, mut wbe) = bgop::new_with(o.bgop.config("bg"));
 
+        let bucket = executor::current_bucket();
         thread::spawn(move || {
-            let mut os = o.op.wr.stream();
+            let mut os = executor::with_bucket(bucket, || o.op.wr.stream());
 
             loop {
                 match rbe.read() {
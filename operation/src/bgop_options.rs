@@ -0,0 +1,59 @@
+use bgop::BgopConfig;
+use bgop::BgopMetrics;
+use bgop::OneBufferMetrics;
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::BooleanOption;
+use opts::vals::OptionalUsizeOption;
+use std::sync::Arc;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct BgopOptions {
+    buffer: OptionalUsizeOption,
+    batch: OptionalUsizeOption,
+    metrics: BooleanOption,
+}
+
+fn parse_positive(a: &str) -> ValidationResult<usize> {
+    let n: usize = a.parse()?;
+    if n == 0 {
+        return ValidationError::message(format!("Must be positive: {}", n));
+    }
+    return Result::Ok(n);
+}
+
+fn fmt_metric(m: &OneBufferMetrics) -> String {
+    return format!("high water {}, stalled {:.3}s", m.high_water, m.stall.as_secs() as f64 + m.stall.subsec_nanos() as f64 * 1e-9);
+}
+
+impl Optionsable for BgopOptions {
+    type Options = BgopOptions;
+
+    fn options(opt: &mut OptionsPile<BgopOptions>) {
+        opt.match_single(&["buffer-size"], |p, a| p.buffer.set(parse_positive(a)?), "maximum number of entries to queue in each direction (default: 1024)");
+        opt.match_single(&["batch-size"], |p, a| p.batch.set(parse_positive(a)?), "number of entries to transfer per lock acquisition (default: 64)");
+        opt.match_zero(&["metrics"], |p| p.metrics.set(), "report queue high-water marks and stall times to stderr at close");
+        opt.match_zero(&["no-metrics"], |p| p.metrics.clear(), "(default)");
+    }
+}
+
+impl BgopOptionsValidated {
+    pub fn config(&self, name: &'static str) -> BgopConfig {
+        let mut config = BgopConfig::default();
+        if let Some(buffer) = self.buffer {
+            config.max_buffer = buffer;
+        }
+        if let Some(batch) = self.batch {
+            config.batch = batch;
+        }
+        if self.metrics {
+            config.on_close = Some(Arc::new(move |m: BgopMetrics| {
+                eprintln!("{}: input: {}; output: {}", name, fmt_metric(&m.fe_to_be), fmt_metric(&m.be_to_fe));
+            }));
+        }
+        return config;
+    }
+}
@@ -26,6 +26,20 @@ impl Optionsable for ClumperOptions {
             }
             return Result::Ok(());
         }, "keys to bucket by");
+        opt.match_single(&["ak", "adjacent-key"], |p, a| {
+            for a in a.split(',') {
+                (p.0).0.push(clumper::adjacent::Impl::init(OneKeyRegistryArgs::new(a)));
+            }
+            return Result::Ok(());
+        }, "keys to bucket adjacent runs by (for sorted input)");
+        opt.match_single(&["cube"], |p, a| {
+            (p.0).0.push(clumper::grouping_sets::cube(a.split(',').map(Arc::from).collect()));
+            return Result::Ok(());
+        }, "keys to bucket by every combination of, with rolled up keys set to 'ALL'");
+        opt.match_single(&["rollup"], |p, a| {
+            (p.0).0.push(clumper::grouping_sets::rollup(a.split(',').map(Arc::from).collect()));
+            return Result::Ok(());
+        }, "keys to bucket by every prefix of, with rolled up keys set to 'ALL'");
     }
 }
 
@@ -0,0 +1,51 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+use super::select::KeysOption;
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    keys: KeysOption,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.add_sub(|p| &mut p.keys, KeysOption::new_options());
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["delete"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "remove specified paths from records";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        return stream::closures(
+            (),
+            move |_s, e, w| {
+                let r = e.parse();
+                return w(Entry::Record(o.keys.delete(r)));
+            },
+            |_s, _w| {
+            },
+        );
+    }
+}
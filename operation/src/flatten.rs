@@ -0,0 +1,98 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::DefaultedStringOption;
+use opts::vals::OptionalUsizeOption;
+use opts::vals::StringVecOption;
+use record::Record;
+use record::RecordTrait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+
+option_defaulters! {
+    SlashDefaulter: String => "/".to_string(),
+}
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    sep: DefaultedStringOption<SlashDefaulter>,
+    depth: OptionalUsizeOption,
+    keys: StringVecOption,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.match_single(&["s", "sep", "separator"], |p, a| p.sep.set_str(a), "separator to join keys with (default: '/')");
+        opt.match_single(&["d", "depth"], |p, a| p.depth.parse(a), "maximum number of levels to flatten (default: all)");
+        opt.match_single(&["k", "key"], |p, a| p.keys.push_split(a), "top-level keys to flatten (default: all)");
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["flatten"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "turn nested hashes and arrays into top-level keys";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        return stream::closures(
+            (),
+            move |_s, e, w| {
+                let r = e.parse();
+
+                let mut hash = BTreeMap::new();
+                for (k, v) in r.expect_hash() {
+                    if o.keys.is_empty() || o.keys.iter().any(|k2| k2 as &str == k as &str) {
+                        flatten_aux(&o, &mut hash, k.to_string(), v, 0);
+                    }
+                    else {
+                        hash.insert(k.clone(), v.clone());
+                    }
+                }
+
+                return w(Entry::Record(Record::from_hash(hash)));
+            },
+            |_s, _w| {
+            },
+        );
+    }
+}
+
+fn flatten_aux(o: &OptionsValidated, acc: &mut BTreeMap<Arc<str>, Record>, prefix: String, v: &Record, depth: usize) {
+    if o.depth.map(|max| depth < max).unwrap_or(true) {
+        if let Some(hash) = v.maybe_hash() {
+            if !hash.is_empty() {
+                for (k, v) in hash {
+                    flatten_aux(o, acc, format!("{}{}{}", prefix, o.sep, k), v, depth + 1);
+                }
+                return;
+            }
+        }
+        if let Some(arr) = v.maybe_array() {
+            if !arr.is_empty() {
+                for (i, v) in arr.iter().enumerate() {
+                    flatten_aux(o, acc, format!("{}{}#{}", prefix, o.sep, i), v, depth + 1);
+                }
+                return;
+            }
+        }
+    }
+
+    acc.insert(Arc::from(prefix), v.clone());
+}
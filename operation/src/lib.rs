@@ -21,6 +21,9 @@ extern crate validates_derive;
 mod tru;
 pub(crate) use self::tru::TwoRecordUnionOption;
 
+mod bgop_options;
+pub(crate) use self::bgop_options::BgopOptions;
+
 mod clumper_options;
 pub(crate) use self::clumper_options::ClumperOptions;
 
@@ -49,14 +52,17 @@ pub type BoxedOperation = Box<OperationInbox>;
 registry! {
     BoxedOperation,
     aggregate,
+    annotate,
     bg,
     chain,
     collate,
     decollate,
+    delete,
     deparse,
     eval,
     expand_files,
     expand_lines,
+    flatten,
     from_multi_regex,
     from_regex,
     from_split,
@@ -66,10 +72,15 @@ registry! {
     multiplex,
     parse,
     provenance,
+    rename,
+    reorder,
+    select,
     shell,
     sort,
     to_ptable,
     to_table,
+    unflatten,
+    uniq,
     wrap_lines,
     xform,
 }
@@ -215,3 +226,6 @@ impl<B: OperationBe2> OperationBe for OperationBeForBe2<B> {
         return B::stream(p.p.clone());
     }
 }
+
+#[cfg(test)]
+mod tests;
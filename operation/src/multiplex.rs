@@ -6,6 +6,7 @@ use super::ClumperOptions;
 use super::OperationBe;
 use super::OperationRegistrant;
 use super::SubOperationOption;
+use validates::ValidationError;
 
 #[derive(Default)]
 #[derive(Validates)]
@@ -22,11 +23,24 @@ impl Optionsable for ImplBe {
     type Options = Options;
 
     fn options(opt: &mut OptionsPile<Options>) {
-        opt.match_extra_hard(|p, a| p.op.push(a), "operation to run in each bucket");
+        opt.match_zero(&["interpolate"], |p| p.op.set_interpolate(), "Replace '%{key}' in the operation's arguments with the bucket's value of key ('%%{' for a literal '%{')");
+        opt.match_extra_hard(|p, a| p.op.push(a), "operation to run in each bucket (its code sees the bucket as $BUCKET/BUCKET)");
         opt.add_sub(|p| &mut p.cl, ClumperOptions::new_options());
     }
 }
 
+// Drops a bucket's records (its sub-operation couldn't be set up).
+fn skip() -> Stream {
+    return stream::closures(
+        (),
+        |_s, _e, _w| {
+            return false;
+        },
+        |_s, _w| {
+        },
+    );
+}
+
 impl OperationBe for ImplBe {
     fn names() -> Vec<&'static str> {
         return vec!["multiplex"];
@@ -43,13 +57,27 @@ impl OperationBe for ImplBe {
     fn stream(o: Arc<OptionsValidated>) -> Stream {
         let o2 = o.clone();
         return o.cl.stream(move |bucket| {
+            let mut br = executor::current_bucket();
+            for (path, v) in &bucket {
+                br.set_path(&path, v.clone());
+            }
+            let os = match executor::with_bucket(br.clone(), || o2.op.stream_for(&br)) {
+                Result::Ok(os) => os,
+                Result::Err(ValidationError::Message(msg)) => {
+                    eprintln!("multiplex: skipping bucket: {}", msg);
+                    return skip();
+                }
+                Result::Err(ValidationError::Help(_)) => {
+                    return skip();
+                }
+            };
             let s = stream::transform_records(move |mut r| {
                 for (path, v) in &bucket {
                     r.set_path(&path, v.clone());
                 }
                 return r;
             });
-            return stream::compound(o2.op.wr.stream(), s);
+            return stream::compound(os, s);
         });
     }
 }
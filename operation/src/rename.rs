@@ -0,0 +1,100 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::UnvalidatedOption;
+use record::Record;
+use record::RecordTrait;
+use regex::Regex;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+use super::select::validate_path;
+
+#[derive(Clone)]
+enum Rename {
+    Path(String, String),
+    Regex(Arc<Regex>, String),
+}
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    renames: UnvalidatedOption<Vec<Rename>>,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.match_n(&["r", "rename"], 2, |p, a| {
+            validate_path(&a[0])?;
+            validate_path(&a[1])?;
+            p.renames.0.push(Rename::Path(a[0].clone(), a[1].clone()));
+            return Result::Ok(());
+        }, ("<from> <to>", "path to move and path to move it to (either may be nested, left alone if the destination runs through a non-hash/non-array)"));
+        opt.match_n(&["re", "regex"], 2, |p, a| {
+            p.renames.0.push(Rename::Regex(Arc::new(Regex::new(&a[0])?), a[1].clone()));
+            return Result::Ok(());
+        }, ("<regex> <replacement>", "rename top-level keys matching regex (replacement may use $1, etc.)"));
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["rename"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "move values between paths of records";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        return stream::closures(
+            (),
+            move |_s, e, w| {
+                let mut r = e.parse();
+
+                for rename in o.renames.iter() {
+                    match rename {
+                        Rename::Path(from, to) => {
+                            if r.has_path(from) {
+                                let mut r2 = r.clone();
+                                let v = r2.del_path(from);
+                                if r2.can_set_path(to) {
+                                    r2.set_path(to, v);
+                                    r = r2;
+                                }
+                            }
+                        }
+                        Rename::Regex(re, replacement) => {
+                            // Literal top-level keys on both sides (and
+                            // nothing to rename in a non-hash).
+                            let mut hash = match r.maybe_hash() {
+                                Some(hash) => hash.clone(),
+                                None => continue,
+                            };
+                            let pairs: Vec<_> = hash.keys().filter(|k| re.is_match(k)).map(|k| (k.clone(), re.replace(k, &replacement as &str).to_string())).collect();
+                            let vs: Vec<_> = pairs.into_iter().map(|(from, to)| (hash.remove(&from).unwrap(), to)).collect();
+                            for (v, to) in vs {
+                                hash.insert(Arc::from(to), v);
+                            }
+                            r = Record::from_hash(hash);
+                        }
+                    }
+                }
+
+                return w(Entry::Record(r));
+            },
+            |_s, _w| {
+            },
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::StringVecOption;
+use record::Record;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    first: StringVecOption,
+    last: StringVecOption,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.match_single(&["k", "key", "first"], |p, a| p.first.push_split(a), "top-level keys to put first, in this order");
+        opt.match_single(&["last"], |p, a| p.last.push_split(a), "top-level keys to put last, in this order");
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["reorder"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "deparse records into lines with chosen keys first/last (records themselves always keep keys sorted, so this must come last)";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        return stream::closures(
+            (),
+            move |_s, e, w| {
+                let r = e.parse();
+                let hash = match r.maybe_hash() {
+                    Some(hash) => hash,
+                    None => return w(Entry::Record(r)),
+                };
+
+                let mut keys: Vec<&str> = Vec::new();
+                for k in o.first.iter() {
+                    if hash.contains_key(k as &str) && !keys.contains(&&k[..]) {
+                        keys.push(k);
+                    }
+                }
+                for k in hash.keys() {
+                    if !keys.contains(&&k[..]) && !o.last.iter().any(|k2| **k == **k2) {
+                        keys.push(k);
+                    }
+                }
+                for k in o.last.iter() {
+                    if hash.contains_key(k as &str) && !keys.contains(&&k[..]) {
+                        keys.push(k);
+                    }
+                }
+
+                let mut line = String::new();
+                line.push_str("{");
+                for (i, k) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        line.push_str(",");
+                    }
+                    line.push_str(&Record::from(k).deparse());
+                    line.push_str(":");
+                    line.push_str(&hash[k].deparse());
+                }
+                line.push_str("}");
+
+                return w(Entry::Line(Arc::from(line)));
+            },
+            |_s, _w| {
+            },
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::StringVecOption;
+use opts::vals::UnvalidatedOption;
+use record::Path;
+use record::Record;
+use record::RecordTrait;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+pub(crate) fn validate_path(path: &str) -> ValidationResult<()> {
+    if path.is_empty() || !Path::is_valid(path) {
+        return ValidationError::message(format!("Invalid path: {}", path));
+    }
+    return Result::Ok(());
+}
+
+// Later array elements first, so deleting one doesn't shift another.
+fn cmp_for_delete(p1: &str, p2: &str) -> Ordering {
+    for (s1, s2) in p1.split('/').zip(p2.split('/')) {
+        let ret = match (s1.starts_with('#'), s2.starts_with('#')) {
+            (true, true) => s2[1..].parse::<usize>().unwrap().cmp(&s1[1..].parse::<usize>().unwrap()),
+            _ => s1.cmp(s2),
+        };
+        if ret != Ordering::Equal {
+            return ret;
+        }
+    }
+    return p1.split('/').count().cmp(&p2.split('/').count());
+}
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct KeysOption {
+    keys: StringVecOption,
+    res: UnvalidatedOption<Vec<Arc<Regex>>>,
+}
+
+impl Optionsable for KeysOption {
+    type Options = KeysOption;
+
+    fn options(opt: &mut OptionsPile<KeysOption>) {
+        opt.match_single(&["k", "key"], |p, a| {
+            for k in a.split(',') {
+                validate_path(k)?;
+            }
+            return p.keys.push_split(a);
+        }, "paths to match");
+        opt.match_single(&["re", "regex"], |p, a| {
+            p.res.0.push(Arc::new(Regex::new(a)?));
+            return Result::Ok(());
+        }, "regex to match top-level keys against");
+    }
+}
+
+// Records that aren't hashes have no keys to pick from and are passed
+// through as is.
+impl KeysOptionValidated {
+    // Regex matches are literal top-level keys (never parsed as paths).
+    fn matching_keys(&self, hash: &BTreeMap<Arc<str>, Record>) -> Vec<Arc<str>> {
+        return hash.keys().filter(|k| self.res.iter().any(|re| re.is_match(k))).cloned().collect();
+    }
+
+    pub fn select(&self, r: &Record) -> Record {
+        let r_hash = match r.maybe_hash() {
+            Some(hash) => hash,
+            None => return r.clone(),
+        };
+        let mut hash = BTreeMap::new();
+        for k in self.matching_keys(r_hash) {
+            hash.insert(k.clone(), r_hash[&k].clone());
+        }
+        let mut r2 = Record::from_hash(hash);
+        for path in self.keys.iter() {
+            if r.has_path(path) {
+                r2.set_path(path, r.get_path(path));
+            }
+        }
+        return r2;
+    }
+
+    pub fn delete(&self, mut r: Record) -> Record {
+        let keys = match r.maybe_hash() {
+            Some(hash) => self.matching_keys(hash),
+            None => return r,
+        };
+        if !keys.is_empty() {
+            let mut hash = r.expect_hash().clone();
+            for k in keys {
+                hash.remove(&k);
+            }
+            r = Record::from_hash(hash);
+        }
+        let mut paths: Vec<_> = self.keys.iter().collect();
+        paths.sort_by(|p1, p2| cmp_for_delete(p1, p2));
+        for path in paths {
+            if r.has_path(path) {
+                r.del_path(path);
+            }
+        }
+        return r;
+    }
+}
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    keys: KeysOption,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.add_sub(|p| &mut p.keys, KeysOption::new_options());
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["select"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "keep only specified paths of records";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        return stream::closures(
+            (),
+            move |_s, e, w| {
+                let r = e.parse();
+                return w(Entry::Record(o.keys.select(&r)));
+            },
+            |_s, _w| {
+            },
+        );
+    }
+}
@@ -11,18 +11,27 @@ use std::sync::Arc;
 use std::thread;
 use stream::Entry;
 use stream::Stream;
+use super::BgopOptions;
 use super::OperationBe;
 use super::OperationRegistrant;
 
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    bgop: BgopOptions,
+    cmd: StringVecOption,
+}
+
 pub(crate) type Impl = OperationRegistrant<ImplBe>;
 
 pub(crate) struct ImplBe();
 
 impl Optionsable for ImplBe {
-    type Options = StringVecOption;
+    type Options = Options;
 
-    fn options(opt: &mut OptionsPile<StringVecOption>) {
-        opt.match_extra_hard(StringVecOption::push_all, "[external] command to run");
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.add_sub(|p| &mut p.bgop, BgopOptions::new_options());
+        opt.match_extra_hard(|p, a| p.cmd.push_all(a), "[external] command to run");
     }
 }
 
@@ -35,12 +44,12 @@ impl OperationBe for ImplBe {
         return "run an external process";
     }
 
-    fn get_extra(_o: Arc<Vec<String>>) -> Vec<String> {
+    fn get_extra(_o: Arc<OptionsValidated>) -> Vec<String> {
         return vec![];
     }
 
-    fn stream(o: Arc<Vec<String>>) -> Stream {
-        let mut args = o.iter();
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        let mut args = o.cmd.iter();
         let mut p = Command::new(args.next().unwrap())
             .args(args)
             .stdin(Stdio::piped())
@@ -48,7 +57,7 @@ impl OperationBe for ImplBe {
             .spawn()
             .unwrap();
 
-        let (fe, rbe, wbe) = bgop::new();
+        let (fe, mut rbe, mut wbe) = bgop::new_with(o.bgop.config("shell"));
         let p_stdin = p.stdin.take().unwrap();
         let p_stdout = p.stdout.take().unwrap();
 
@@ -1,8 +1,13 @@
 use opts::parser::OptionsPile;
 use opts::parser::Optionsable;
 use opts::vals::OptionalUsizeOption;
+use record::Record;
+use record::RecordTrait;
+use record::SpillFile;
 use registry::Registrant;
-use registry::args::OneKeyRegistryArgs;
+use sorts::SimpleSortArgs;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::sync::Arc;
 use stream::Entry;
 use stream::Stream;
@@ -17,6 +22,7 @@ use super::SortOptions;
 pub struct Options {
     sorts: SortOptions,
     partial: OptionalUsizeOption,
+    max_memory: OptionalUsizeOption,
 }
 
 pub(crate) type Impl = OperationRegistrant<ImplBe>;
@@ -29,21 +35,22 @@ impl Optionsable for ImplBe2 {
     type Options = Options;
 
     fn options(opt: &mut OptionsPile<Options>) {
-        opt.add_sub(|p| &mut p.sorts, SortOptions::new_options(&["s", "sort"], "sorts"));
+        opt.add_sub(|p| &mut p.sorts, SortOptions::new_options(&["s", "sort"], "sorts, as name,arg,... or, for sorts taking a single arg, name:arg (e.g. n:-x)"));
         opt.add(SortOptions::help_options());
         opt.match_single(&["l", "lex", "lexical"], |p, a| {
             for a in a.split(',') {
-                p.sorts.push(sorts::lexical::Impl::init(OneKeyRegistryArgs::new(a)));
+                p.sorts.push(sorts::lexical::Impl::init(SimpleSortArgs::new(a)?));
             }
             return Result::Ok(());
-        }, "keys to sort by lexically, prefix with minus to sort descending");
+        }, "keys to sort by lexically, prefix with minus to sort descending, suffix with :nullsfirst or :nullslast to place nulls (default: first ascending, last descending)");
         opt.match_single(&["n", "num", "numeric"], |p, a| {
             for a in a.split(',') {
-                p.sorts.push(sorts::numeric::Impl::init(OneKeyRegistryArgs::new(a)));
+                p.sorts.push(sorts::numeric::Impl::init(SimpleSortArgs::new(a)?));
             }
             return Result::Ok(());
-        }, "keys to sort by numerically, prefix with minus to sort descending");
+        }, "keys to sort by numerically, prefix with minus to sort descending, suffix with :nullsfirst or :nullslast to place nulls (default: first ascending, last descending)");
         opt.match_single(&["p", "partial"], |p, a| p.partial.parse(a), "limit output to this many [first] records");
+        opt.match_single(&["max-memory"], |p, a| p.max_memory.parse_size(a), "spill sorted runs to temporary files once buffered records exceed roughly this many bytes (suffixes K, M, G allowed)");
     }
 }
 
@@ -57,6 +64,10 @@ impl OperationBe2 for ImplBe2 {
     }
 
     fn stream(o: Arc<OptionsValidated>) -> Stream {
+        if let Some(max_memory) = o.max_memory {
+            return external_stream(o.clone(), max_memory);
+        }
+
         struct State {
             o: Arc<OptionsValidated>,
             rs: GenericSortBucket<()>,
@@ -91,3 +102,165 @@ impl OperationBe2 for ImplBe2 {
         );
     }
 }
+
+struct ExternalEntry {
+    k: Vec<Record>,
+    i: usize,
+    r: Record,
+}
+
+impl ExternalEntry {
+    fn cmp(&self, other: &ExternalEntry, o: &OptionsValidated) -> Ordering {
+        return o.sorts.cmp_keys(&self.k, &other.k).then(self.i.cmp(&other.i));
+    }
+
+    fn to_record(self) -> Record {
+        return Record::from_vec(vec![Record::from(self.i as i64), Record::from_vec(self.k), self.r]);
+    }
+
+    fn from_record(r: Record) -> ExternalEntry {
+        let arr = r.expect_array();
+        return ExternalEntry {
+            k: arr[1].expect_array().clone(),
+            i: arr[0].coerce_num().map_right(|f| f as i64).join() as usize,
+            r: arr[2].clone(),
+        };
+    }
+}
+
+struct MergeHead {
+    o: Arc<OptionsValidated>,
+    e: ExternalEntry,
+    run: usize,
+}
+
+impl PartialEq for MergeHead {
+    fn eq(&self, other: &MergeHead) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for MergeHead {
+}
+
+impl PartialOrd for MergeHead {
+    fn partial_cmp(&self, other: &MergeHead) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for MergeHead {
+    fn cmp(&self, other: &MergeHead) -> Ordering {
+        // reversed since BinaryHeap pops the greatest
+        return self.e.cmp(&other.e, &self.o).reverse();
+    }
+}
+
+struct ExternalState {
+    o: Arc<OptionsValidated>,
+    max_memory: usize,
+    es: Vec<ExternalEntry>,
+    size: usize,
+    i: usize,
+    runs: Vec<SpillFile>,
+}
+
+impl ExternalState {
+    fn take_sorted(&mut self) -> Vec<ExternalEntry> {
+        let mut es = std::mem::replace(&mut self.es, Vec::new());
+        self.size = 0;
+        let o = self.o.clone();
+        es.sort_by(|e1, e2| e1.cmp(e2, &o));
+        if let Some(limit) = o.partial {
+            es.truncate(limit);
+        }
+        return es;
+    }
+
+    fn spill(&mut self) {
+        let es = self.take_sorted();
+        let (f, mut fw) = SpillFile::new();
+        for e in es {
+            fw.write(&e.to_record());
+        }
+        fw.finish();
+        self.runs.push(f);
+    }
+}
+
+fn external_stream(o: Arc<OptionsValidated>, max_memory: usize) -> Stream {
+    return stream::closures(
+        ExternalState {
+            o: o,
+            max_memory: max_memory,
+            es: Vec::new(),
+            size: 0,
+            i: 0,
+            runs: Vec::new(),
+        },
+        |s, e, _w| {
+            let r = e.parse();
+
+            let k = s.o.sorts.key(&r);
+            s.size += record::approx_size(&r) + k.iter().map(record::approx_size).sum::<usize>();
+            s.es.push(ExternalEntry {
+                k: k,
+                i: s.i,
+                r: r,
+            });
+            s.i += 1;
+
+            if s.size > s.max_memory {
+                s.spill();
+            }
+
+            return true;
+        },
+        |mut s, w| {
+            if s.runs.is_empty() {
+                for e in s.take_sorted() {
+                    if !w(Entry::Record(e.r)) {
+                        return;
+                    }
+                }
+                return;
+            }
+
+            s.spill();
+
+            let mut readers: Vec<_> = s.runs.iter().map(|f| f.read()).collect();
+            let mut heap = BinaryHeap::new();
+            for (run, reader) in readers.iter_mut().enumerate() {
+                if let Some(r) = reader.next() {
+                    heap.push(MergeHead {
+                        o: s.o.clone(),
+                        e: ExternalEntry::from_record(r),
+                        run: run,
+                    });
+                }
+            }
+
+            let mut ct = 0;
+            while let Some(head) = heap.pop() {
+                if let Some(limit) = s.o.partial {
+                    if ct >= limit {
+                        return;
+                    }
+                }
+                ct += 1;
+
+                let run = head.run;
+                if !w(Entry::Record(head.e.r)) {
+                    return;
+                }
+                if let Some(r) = readers[run].next() {
+                    heap.push(MergeHead {
+                        o: s.o.clone(),
+                        e: ExternalEntry::from_record(r),
+                        run: run,
+                    });
+                }
+            }
+        },
+    );
+}
@@ -5,6 +5,7 @@ use record::Record;
 use sorts::BoxedSort;
 use sorts::bucket::SortBucket;
 use sorts::bucket::VecDequeSortBucket;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -14,7 +15,10 @@ pub struct SortOptions(UnvalidatedOption<Vec<BoxedSort>>);
 
 impl SortOptions {
     pub fn options(opt: &mut OptionsPile<SortOptions>, aliases: &[&str], help: impl ToOptionsHelp) {
-        opt.add_sub(|p| &mut (p.0).0, sorts::REGISTRY.single_options(aliases, help));
+        opt.match_single(aliases, |p, a| {
+            p.push(sorts::parse(a)?);
+            return Result::Ok(());
+        }, help);
         opt.add_sub(|p| &mut (p.0).0, sorts::REGISTRY.multiple_options(aliases));
     }
 
@@ -85,4 +89,18 @@ impl SortOptionsValidated {
             bucket: f(),
         };
     }
+
+    pub fn key(&self, r: &Record) -> Vec<Record> {
+        return self.0.iter().map(|sort| sort.key(r)).collect();
+    }
+
+    pub fn cmp_keys(&self, k1: &[Record], k2: &[Record]) -> Ordering {
+        for ((sort, k1), k2) in self.0.iter().zip(k1.iter()).zip(k2.iter()) {
+            let ret = sort.cmp_keys(k1, k2);
+            if ret != Ordering::Equal {
+                return ret;
+            }
+        }
+        return Ordering::Equal;
+    }
 }
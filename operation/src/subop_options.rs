@@ -1,42 +1,150 @@
+use record::Record;
+use record::Path;
+use record::RecordTrait;
 use std::sync::Arc;
+use stream::Stream;
 use super::StreamWrapper;
 use validates::Validates;
 use validates::ValidationError;
 use validates::ValidationResult;
 
 #[derive(Default)]
-pub struct SubOperationOption(Vec<String>);
+pub struct SubOperationOption {
+    args: Vec<String>,
+    interpolate: bool,
+}
 
 impl SubOperationOption {
     pub fn push(&mut self, a: &[String]) -> ValidationResult<()> {
-        self.0.extend_from_slice(a);
+        self.args.extend_from_slice(a);
+        return Result::Ok(());
+    }
+
+    pub fn set_interpolate(&mut self) -> ValidationResult<()> {
+        self.interpolate = true;
         return Result::Ok(());
     }
 
     pub fn of(a: Vec<String>) -> SubOperationOption {
-        return SubOperationOption(a);
+        return SubOperationOption {
+            args: a,
+            interpolate: false,
+        };
     }
 }
 
 impl Validates for SubOperationOption {
     type Target = SubOperationOptionValidated;
 
-    fn validate(mut self) -> ValidationResult<SubOperationOptionValidated> {
-        if self.0.len() == 0 {
+    fn validate(self) -> ValidationResult<SubOperationOptionValidated> {
+        if self.args.len() == 0 {
             return ValidationError::message("No sub-operation specified");
         }
-        let name = self.0.remove(0);
-        let op = super::REGISTRY.find(&name, &[])?;
-        let wr = op.parse(&mut self.0)?;
+        if !self.interpolate {
+            let (wr, extra) = parse(self.args)?;
+            return Result::Ok(SubOperationOptionValidated {
+                extra: extra,
+                wr: Arc::new(wr),
+                template: None,
+            });
+        }
+        let template = self.args.iter().map(|a| parse_template(a)).collect::<ValidationResult<Vec<_>>>()?;
+        // With placeholders left as they are, this checks the template parses
+        // (so values can only go where the sub-operation takes free text).
+        // Without any it is the sub-operation itself.
+        let (wr, extra) = parse(render(&template, |path| format!("%{{{}}}", path)))?;
+        let has_paths = template.iter().any(|parts| parts.iter().any(|part| part.is_path()));
         return Result::Ok(SubOperationOptionValidated {
-            extra: self.0,
+            extra: extra,
             wr: Arc::new(wr),
+            template: match has_paths {
+                true => Some(Arc::new(template)),
+                false => None,
+            },
         });
     }
 }
 
+fn parse(mut args: Vec<String>) -> ValidationResult<(StreamWrapper, Vec<String>)> {
+    let name = args.remove(0);
+    let op = super::REGISTRY.find(&name, &[])?;
+    let wr = op.parse(&mut args)?;
+    return Result::Ok((wr, args));
+}
+
+enum TemplatePart {
+    Literal(String),
+    Path(String),
+}
+
+impl TemplatePart {
+    fn is_path(&self) -> bool {
+        return match self {
+            TemplatePart::Literal(_) => false,
+            TemplatePart::Path(_) => true,
+        };
+    }
+}
+
+// "%{path}" is a value from the bucket and "%%{" a literal "%{".
+fn parse_template(a: &str) -> ValidationResult<Vec<TemplatePart>> {
+    let mut parts = Vec::new();
+    let mut lit = String::new();
+    let mut rest = a;
+    while let Some(start) = rest.find("%{") {
+        if rest[..start].ends_with('%') {
+            lit.push_str(&rest[..(start - 1)]);
+            lit.push_str("%{");
+            rest = &rest[(start + 2)..];
+            continue;
+        }
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => return ValidationError::message(format!("Unterminated %{{ in sub-operation argument: {}", a)),
+        };
+        let path = &rest[(start + 2)..end];
+        if path.is_empty() || !Path::is_valid(path) {
+            return ValidationError::message(format!("Invalid path in sub-operation argument: {}", a));
+        }
+        lit.push_str(&rest[..start]);
+        parts.push(TemplatePart::Literal(std::mem::replace(&mut lit, String::new())));
+        parts.push(TemplatePart::Path(path.to_string()));
+        rest = &rest[(end + 1)..];
+    }
+    lit.push_str(rest);
+    parts.push(TemplatePart::Literal(lit));
+    return Result::Ok(parts);
+}
+
+fn render(template: &[Vec<TemplatePart>], f: impl Fn(&str) -> String) -> Vec<String> {
+    return template.iter().map(|parts| {
+        return parts.iter().map(|part| {
+            return match part {
+                TemplatePart::Literal(s) => s.clone(),
+                TemplatePart::Path(path) => f(path),
+            };
+        }).collect::<String>();
+    }).collect();
+}
+
 #[derive(Clone)]
 pub struct SubOperationOptionValidated {
     pub extra: Vec<String>,
     pub wr: Arc<StreamWrapper>,
+    template: Option<Arc<Vec<Vec<TemplatePart>>>>,
+}
+
+impl SubOperationOptionValidated {
+    // Stream with the template's paths filled in from the bucket (reparsing
+    // the sub-operation if there were any), failing if the values don't make
+    // for valid arguments.
+    pub fn stream_for(&self, bucket: &Record) -> ValidationResult<Stream> {
+        let template = match self.template {
+            Some(ref template) => template,
+            None => return Result::Ok(self.wr.stream()),
+        };
+        let args = render(template, |path| bucket.get_path(path).coerce_string().to_string());
+        let (wr, _extra) = parse(args).map_err(|e| e.label(format!("Sub-operation for bucket {}", bucket.deparse())))?;
+        return Result::Ok(wr.stream());
+    }
 }
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+use stream::Entry;
+use validates::ValidationError;
+
+fn parse(args: &[&str]) -> Result<super::StreamWrapper, String> {
+    let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    let name = args.remove(0);
+    let op = super::REGISTRY.find(&name, &[]).unwrap_or_else(|e| e.panic());
+    return op.parse(&mut args).map_err(|e| match e {
+        ValidationError::Message(s) => s,
+        ValidationError::Help(lines) => lines.join("\n"),
+    });
+}
+
+fn run(args: &[&str], input: &[&str]) -> Vec<String> {
+    let op = parse(args).unwrap_or_else(|s| panic!("{}", s));
+    let mut os = op.stream();
+    let mut out = Vec::new();
+    for line in input {
+        if !os.write(Entry::Line(Arc::from(*line)), &mut |e| {
+            out.push(e.deparse().to_string());
+            return true;
+        }) {
+            break;
+        }
+    }
+    os.close(&mut |e| {
+        out.push(e.deparse().to_string());
+        return true;
+    });
+    return out;
+}
+
+fn numbered(n: usize) -> Vec<String> {
+    // A little scrambled, with duplicate keys to check stability.
+    return (0..n).map(|i| format!(r#"{{"k":{},"i":{}}}"#, (i * 7919) % 37, i)).collect();
+}
+
+fn strs(v: &[String]) -> Vec<&str> {
+    return v.iter().map(|s| &s[..]).collect();
+}
+
+#[test]
+fn test_sort_spill_matches_memory() {
+    let input = numbered(500);
+    let input = strs(&input);
+    for sorts in &[&["-n", "k"][..], &["-n", "-k"][..], &["-s", "lexical,k", "-n", "-i"][..]] {
+        let mut mem = vec!["sort"];
+        mem.extend(sorts.iter());
+        let mut ext = mem.clone();
+        ext.extend(&["--max-memory", "200"]);
+        assert_eq!(run(&ext, &input), run(&mem, &input));
+    }
+}
+
+#[test]
+fn test_sort_spill_nulls() {
+    // Nulls, missing keys and non-numeric values, through the spilled keys.
+    let input: Vec<_> = (0..300).map(|i| match i % 5 {
+        0 => format!(r#"{{"k":null,"i":{}}}"#, i),
+        1 => format!(r#"{{"i":{}}}"#, i),
+        2 => format!(r#"{{"k":"x{}","i":{}}}"#, i % 7, i),
+        _ => format!(r#"{{"k":{},"i":{}}}"#, (i * 7919) % 37, i),
+    }).collect();
+    let input = strs(&input);
+    for sorts in &[&["-n", "k"][..], &["-n", "-k"][..], &["-n", "k:nullslast"][..], &["-l", "-k:nullsfirst"][..], &["-s", "expr,{{k}},nullslast"][..]] {
+        let mut mem = vec!["sort"];
+        mem.extend(sorts.iter());
+        let mut ext = mem.clone();
+        ext.extend(&["--max-memory", "200"]);
+        assert_eq!(run(&ext, &input), run(&mem, &input));
+    }
+    let out = run(&["sort", "-n", "k:nullslast", "--max-memory", "200"], &input);
+    assert!(out[..180].iter().all(|s| !s.contains("null") && s.contains("\"k\"")));
+    assert!(out[180..].iter().all(|s| s.contains("null") || !s.contains("\"k\"")));
+}
+
+#[test]
+fn test_sort_spill_partial() {
+    let input = numbered(300);
+    let input = strs(&input);
+    let mem = run(&["sort", "-n", "k", "-p", "10"], &input);
+    assert_eq!(mem.len(), 10);
+    assert_eq!(run(&["sort", "-n", "k", "-p", "10", "--max-memory", "100"], &input), mem);
+}
+
+#[test]
+fn test_max_memory_overflow() {
+    let e = parse(&["sort", "-n", "k", "--max-memory", "99999999999999999G"]).err().unwrap();
+    assert!(e.contains("Size too large"), "{}", e);
+    assert!(parse(&["sort", "-n", "k", "--max-memory", "10M"]).is_ok());
+}
+
+#[test]
+fn test_uniq_adjacent() {
+    let input = [r#"{"a":1,"b":1}"#, r#"{"a":1,"b":2}"#, r#"{"a":2}"#, r#"{"a":1}"#];
+    assert_eq!(run(&["uniq", "-k", "a"], &input), vec![r#"{"a":1,"b":1}"#, r#"{"a":2}"#, r#"{"a":1}"#]);
+    assert_eq!(run(&["uniq", "-k", "a", "-c", "n"], &input), vec![r#"{"a":1,"b":1,"n":2}"#, r#"{"a":2,"n":1}"#, r#"{"a":1,"n":1}"#]);
+    assert_eq!(run(&["uniq"], &[r#"{"a":1}"#, r#"{"a":1}"#, r#"{"a":1,"b":1}"#]), vec![r#"{"a":1}"#, r#"{"a":1,"b":1}"#]);
+}
+
+#[test]
+fn test_uniq_global() {
+    let input = [r#"{"a":1}"#, r#"{"a":2}"#, r#"{"a":1}"#, r#"{"a":3}"#, r#"{"a":2}"#];
+    assert_eq!(run(&["uniq", "-g", "-k", "a"], &input), vec![r#"{"a":1}"#, r#"{"a":2}"#, r#"{"a":3}"#]);
+    assert_eq!(run(&["uniq", "-g", "-k", "a", "-c", "n"], &input), vec![r#"{"a":1,"n":2}"#, r#"{"a":2,"n":2}"#, r#"{"a":3,"n":1}"#]);
+}
+
+#[test]
+fn test_uniq_bloom() {
+    let input: Vec<_> = (0..200).map(|i| format!(r#"{{"a":{}}}"#, i % 50)).collect();
+    let out = run(&["uniq", "--bloom", "64K", "-k", "a"], &strs(&input));
+    // No false negatives; with this much room no false positives either.
+    assert_eq!(out, strs(&input[0..50].to_vec()));
+    assert!(parse(&["uniq", "--bloom", "1K", "-c", "n"]).is_err());
+}
+
+#[test]
+fn test_select() {
+    let input = [r#"{"a":{"b":1,"c":2},"x1":1,"x2":2,"y":[5,6]}"#];
+    assert_eq!(run(&["select", "-k", "a/b,y/#1,nope"], &input), vec![r#"{"a":{"b":1},"y":[null,6]}"#]);
+    assert_eq!(run(&["select", "--re", "^x"], &input), vec![r#"{"x1":1,"x2":2}"#]);
+    // Running through a non-hash just doesn't match.
+    assert_eq!(run(&["select", "-k", "x1/z"], &input), vec![r#"{}"#]);
+    assert!(parse(&["select", "-k", "a/#b"]).is_err());
+}
+
+#[test]
+fn test_delete() {
+    let input = [r#"{"a":{"b":1,"c":2},"x1":1,"y":[5,6,7],"k/l":3}"#];
+    assert_eq!(run(&["delete", "-k", "a/b,nope"], &input), vec![r#"{"a":{"c":2},"k/l":3,"x1":1,"y":[5,6,7]}"#]);
+    // Array elements, later ones first so indices refer to the input.
+    assert_eq!(run(&["delete", "-k", "y/#0,y/#2"], &input), vec![r#"{"a":{"b":1,"c":2},"k/l":3,"x1":1,"y":[6]}"#]);
+    assert_eq!(run(&["delete", "-k", "x1/z,y/#9"], &input), vec![r#"{"a":{"b":1,"c":2},"k/l":3,"x1":1,"y":[5,6,7]}"#]);
+    // Regex keys are literal, even with a slash in them.
+    assert_eq!(run(&["delete", "--re", "/"], &input), vec![r#"{"a":{"b":1,"c":2},"x1":1,"y":[5,6,7]}"#]);
+    assert!(parse(&["delete", "-k", "y/#"]).is_err());
+}
+
+#[test]
+fn test_rename() {
+    let input = [r#"{"a":{"b":1},"s":"x","v":[1,2]}"#];
+    assert_eq!(run(&["rename", "-r", "a/b", "c"], &input), vec![r#"{"a":{},"c":1,"s":"x","v":[1,2]}"#]);
+    assert_eq!(run(&["rename", "-r", "s", "a/s"], &input), vec![r#"{"a":{"b":1,"s":"x"},"v":[1,2]}"#]);
+    assert_eq!(run(&["rename", "-r", "v/#0", "first"], &input), vec![r#"{"a":{"b":1},"first":1,"s":"x","v":[2]}"#]);
+    // The destination runs through a string: left alone.
+    assert_eq!(run(&["rename", "-r", "a", "s/t"], &input), vec![input[0]]);
+    assert_eq!(run(&["rename", "--re", "^(.)$", "$1/x"], &[r#"{"a":1,"bb":2}"#]), vec![r#"{"a/x":1,"bb":2}"#]);
+    assert!(parse(&["rename", "-r", "#x", "y"]).is_err());
+}
+
+#[test]
+fn test_reorder() {
+    let input = [r#"{"a":1,"b":{"y":1,"x":2},"c":3,"d":4}"#];
+    assert_eq!(run(&["reorder", "-k", "c,zz,b"], &input), vec![r#"{"c":3,"b":{"x":2,"y":1},"a":1,"d":4}"#]);
+    assert_eq!(run(&["reorder", "--last", "a", "-k", "d"], &input), vec![r#"{"d":4,"b":{"x":2,"y":1},"c":3,"a":1}"#]);
+}
+
+#[test]
+fn test_keys_non_hash() {
+    // Nothing to match in a non-hash: passed through.
+    let input = ["5", "[1,2]", r#""s""#];
+    assert_eq!(run(&["select", "--re", "^x"], &input), input);
+    assert_eq!(run(&["select", "--re", "^x", "-k", "a"], &input), input);
+    assert_eq!(run(&["delete", "--re", "^x"], &input), input);
+    assert_eq!(run(&["rename", "--re", "^x", "y"], &input), input);
+    assert_eq!(run(&["reorder", "-k", "a"], &input), input);
+}
+
+#[test]
+fn test_unflatten() {
+    assert_eq!(run(&["unflatten"], &[r#"{"a/b":1,"a/c/#1":2,"a/c/#0":3,"d":4}"#]), vec![r#"{"a":{"b":1,"c":[3,2]},"d":4}"#]);
+    // Parts are literal keys, whatever they look like as a path.
+    assert_eq!(run(&["unflatten"], &[r##"{"#":1,"#x/y":2,"a/#z":3}"##]), vec![r##"{"#":1,"#x":{"y":2},"a":{"#z":3}}"##]);
+    // A value and a hash (or array) at the same place: the later key stays flat.
+    assert_eq!(run(&["unflatten"], &[r#"{"a":1,"a/b":2,"a/c/d":3}"#]), vec![r#"{"a":1,"a/b":2,"a/c/d":3}"#]);
+    assert_eq!(run(&["unflatten"], &[r#"{"a/#0":1,"a/b":2}"#]), vec![r#"{"a":[1],"a/b":2}"#]);
+    // Indices can't run past the record's key count (so a stray huge one
+    // doesn't allocate a huge array).
+    assert_eq!(run(&["unflatten"], &[r##"{"a/#1000000000":1,"b/#0/#1000000000":2,"c":3}"##]), vec![r##"{"a/#1000000000":1,"b/#0/#1000000000":2,"c":3}"##]);
+    assert_eq!(run(&["unflatten"], &[r##"{"a/#2":1,"x":2,"y":3}"##]), vec![r##"{"a":[null,null,1],"x":2,"y":3}"##]);
+    // Only the separator splits.
+    assert_eq!(run(&["unflatten", "--sep", "."], &[r#"{"a/b.c":1,"a/b.d":2}"#]), vec![r#"{"a/b":{"c":1,"d":2}}"#]);
+}
+
+#[test]
+fn test_flatten_round_trip() {
+    let input = [r#"{"a":{"b":[1,{"c":2}],"d":{}},"e":"x"}"#];
+    let flat = run(&["flatten"], &input);
+    assert_eq!(flat, vec![r##"{"a/b/#0":1,"a/b/#1/c":2,"a/d":{},"e":"x"}"##]);
+    assert_eq!(run(&["unflatten"], &strs(&flat)), vec![input[0]]);
+}
+
+#[test]
+fn test_multiplex_interpolate() {
+    let input = [r#"{"g":"a"}"#, r#"{"g":"b"}"#];
+    let mut out = run(&["multiplex", "-k", "g", "--interpolate", "--", "xform", r#"{{t}} = "%{g}-%%{g}""#], &input);
+    out.sort();
+    assert_eq!(out, vec![r#"{"g":"a","t":"a-%{g}"}"#, r#"{"g":"b","t":"b-%{g}"}"#]);
+    // Only on request.
+    let mut out = run(&["multiplex", "-k", "g", "--", "xform", r#"{{t}} = "%{g}""#], &input);
+    out.sort();
+    assert_eq!(out, vec![r#"{"g":"a","t":"%{g}"}"#, r#"{"g":"b","t":"%{g}"}"#]);
+    // Checked up front.
+    assert!(parse(&["multiplex", "-k", "g", "--interpolate", "--", "xform", r#"{{t}} = "%{g""#]).is_err());
+    assert!(parse(&["multiplex", "-k", "g", "--interpolate", "--", "xform", r#"{{t}} = "%{#x}""#]).is_err());
+    assert!(parse(&["multiplex", "-k", "g", "--interpolate", "--", "head", "-n", "%{g}"]).is_err());
+    // Escapes are undone even with no placeholders.
+    assert_eq!(run(&["multiplex", "-k", "g", "--interpolate", "--", "xform", r#"{{t}} = "%%{g}""#], &input[..1]), vec![r#"{"g":"a","t":"%{g}"}"#]);
+    // A bucket whose values don't make a valid sub-operation is skipped
+    // (reported on stderr), the rest carry on.
+    let input = [r#"{"g":"a\"b"}"#, r#"{"g":"c"}"#];
+    assert_eq!(run(&["multiplex", "-k", "g", "--interpolate", "--", "xform", r#"{{t}} = "%{g}""#], &input), vec![r#"{"g":"c","t":"c"}"#]);
+}
+
+#[test]
+fn test_multiplex_bucket() {
+    let input = [r#"{"g":"a","x":1}"#, r#"{"g":"a","x":2}"#];
+    assert_eq!(run(&["multiplex", "-k", "g", "--", "xform", "{{t}} = {{BUCKET:g}}"], &input), vec![r#"{"g":"a","t":"a","x":1}"#, r#"{"g":"a","t":"a","x":2}"#]);
+    // Aggregator code is built lazily, on the first record.
+    let input = [r#"{"g":"a"}"#, r#"{"g":"b"}"#];
+    let mut out = run(&["multiplex", "-k", "g", "--", "aggregate", "-a", "c=expr,{{BUCKET:g}},array"], &input);
+    out.sort();
+    assert_eq!(out, vec![r#"{"c":["a"],"g":"a"}"#, r#"{"c":["b"],"g":"b"}"#]);
+}
+
+#[test]
+fn test_aggregate_partial() {
+    let input = numbered(30);
+    let aggs = ["-a", "ct=count", "-a", "s=sum,k", "-a", "m=max,i", "-a", "d=dcount,k"];
+    let whole = run(&[&["aggregate"][..], &aggs[..]].concat(), &strs(&input));
+    let mut partials = Vec::new();
+    for chunk in input.chunks(7) {
+        partials.extend(run(&[&["aggregate", "--emit-partial"][..], &aggs[..]].concat(), &strs(chunk)));
+    }
+    assert_eq!(partials.len(), 5);
+    let merged = run(&[&["aggregate", "--merge-partial"][..], &aggs[..]].concat(), &strs(&partials));
+    assert_eq!(merged, whole);
+    assert_eq!(whole, vec![r#"{"ct":30,"d":30,"m":29,"s":435}"#]);
+    // Refused up front for an aggregator that can't merge.
+    assert!(parse(&["aggregate", "--merge-partial", "-a", "s=eval,{{state}} = 0,{{state}} = 1,{{state}}"]).is_err());
+    assert!(parse(&["aggregate", "-a", "s=eval,{{state}} = 0,{{state}} = 1,{{state}}"]).is_ok());
+}
+
+#[test]
+fn test_aggregate_escaped_commas() {
+    let input = [r#"{"x":1}"#, r#"{"x":5}"#];
+    assert_eq!(run(&["aggregate", "-a", r#"m=expr,lua:return math.max(r["x"]\,3),sum"#], &input), vec![r#"{"m":8}"#]);
+    assert!(parse(&["aggregate", "-a", r#"m=expr,lua:return math.max(r["x"],3),sum"#]).is_err());
+}
+
+#[test]
+fn test_annotate() {
+    let input = [r#"{"g":"a","x":3}"#, r#"{"g":"b","x":1}"#, r#"{"g":"a","x":1}"#, r#"{"g":"a","x":3}"#, r#"{"g":"a","x":7}"#];
+    let out = run(&["annotate", "-k", "g", "-n", "x", "--row-number", "rn", "--rank", "rk", "--dense-rank", "dr", "--percent-rank", "pr", "--cumcount", "cc"], &input);
+    assert_eq!(out, vec![
+        r#"{"cc":1,"dr":1,"g":"a","pr":0.0,"rk":1,"rn":1,"x":1}"#,
+        r#"{"cc":3,"dr":2,"g":"a","pr":33.333333333333336,"rk":2,"rn":2,"x":3}"#,
+        r#"{"cc":3,"dr":2,"g":"a","pr":33.333333333333336,"rk":2,"rn":3,"x":3}"#,
+        r#"{"cc":4,"dr":3,"g":"a","pr":100.0,"rk":4,"rn":4,"x":7}"#,
+        r#"{"cc":1,"dr":1,"g":"b","pr":0.0,"rk":1,"rn":1,"x":1}"#,
+    ]);
+    let out = run(&["annotate", "-n", "-x", "--cumsum", "cs=x", "--pct", "p=x", "--lag", "prev=x", "--lead", "next=x", "--zscore", "z=x"], &input[0..3]);
+    assert_eq!(out, vec![
+        r#"{"cs":3.0,"g":"a","next":1,"p":60.0,"prev":null,"x":3,"z":1.414213562373095}"#,
+        // Ties stay in input order.
+        r#"{"cs":4.0,"g":"b","next":1,"p":20.0,"prev":3,"x":1,"z":-0.7071067811865476}"#,
+        r#"{"cs":5.0,"g":"a","next":null,"p":20.0,"prev":1,"x":1,"z":-0.7071067811865476}"#,
+    ]);
+    assert!(parse(&["annotate", "--cumsum", "x"]).is_err());
+}
@@ -0,0 +1,166 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::DefaultedStringOption;
+use record::Record;
+use record::RecordTrait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+use super::flatten::SlashDefaulter;
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    sep: DefaultedStringOption<SlashDefaulter>,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.match_single(&["s", "sep", "separator"], |p, a| p.sep.set_str(a), "separator to split keys on (default: '/')");
+    }
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["unflatten"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "turn top-level keys containing a separator back into nested hashes and arrays (#N parts are array indices, others literal keys; keys that conflict, or with an index past the record's key count, are left flat)";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        return stream::closures(
+            (),
+            move |_s, e, w| {
+                let r = e.parse();
+
+                let hash = r.expect_hash();
+                let max_index = hash.len();
+                let mut root = BTreeMap::new();
+                for (k, v) in hash {
+                    let mut parts = k.split(&o.sep as &str);
+                    let first = parts.next().unwrap();
+                    let rest: Vec<_> = parts.collect();
+                    let ok = match root.get_mut(first) {
+                        Some(t) => insert(t, &rest, v, max_index),
+                        None => match new_tree(&rest, v, max_index) {
+                            Some(t) => {
+                                root.insert(Arc::from(first), t);
+                                true
+                            }
+                            None => false,
+                        },
+                    };
+                    if !ok {
+                        root.insert(k.clone(), Tree::Leaf(v.clone()));
+                    }
+                }
+
+                return w(Entry::Record(Record::from_hash(root.into_iter().map(|(k, t)| (k, t.into_record())).collect())));
+            },
+            |_s, _w| {
+            },
+        );
+    }
+}
+
+enum Tree {
+    Leaf(Record),
+    Hash(BTreeMap<Arc<str>, Tree>),
+    Array(BTreeMap<usize, Tree>),
+}
+
+fn index(part: &str) -> Option<usize> {
+    if part.starts_with('#') {
+        return part[1..].parse().ok();
+    }
+    return None;
+}
+
+fn new_tree(parts: &[&str], v: &Record, max_index: usize) -> Option<Tree> {
+    if parts.is_empty() {
+        return Some(Tree::Leaf(v.clone()));
+    }
+    let mut t = match index(parts[0]) {
+        Some(_) => Tree::Array(BTreeMap::new()),
+        None => Tree::Hash(BTreeMap::new()),
+    };
+    if !insert(&mut t, parts, v, max_index) {
+        return None;
+    }
+    return Some(t);
+}
+
+// False (leaving t untouched) if parts run into a value or a container of the
+// other kind, or an index too large for the record (an array that would be
+// mostly padding).
+fn insert(t: &mut Tree, parts: &[&str], v: &Record, max_index: usize) -> bool {
+    if parts.is_empty() {
+        return false;
+    }
+    let rest = &parts[1..];
+    match (t, index(parts[0])) {
+        (Tree::Hash(hash), None) => {
+            if let Some(t1) = hash.get_mut(parts[0]) {
+                return insert(t1, rest, v, max_index);
+            }
+            return match new_tree(rest, v, max_index) {
+                Some(t1) => {
+                    hash.insert(Arc::from(parts[0]), t1);
+                    true
+                }
+                None => false,
+            };
+        }
+        (Tree::Array(arr), Some(i)) => {
+            if i >= max_index {
+                return false;
+            }
+            if let Some(t1) = arr.get_mut(&i) {
+                return insert(t1, rest, v, max_index);
+            }
+            return match new_tree(rest, v, max_index) {
+                Some(t1) => {
+                    arr.insert(i, t1);
+                    true
+                }
+                None => false,
+            };
+        }
+        _ => {
+            return false;
+        }
+    }
+}
+
+impl Tree {
+    fn into_record(self) -> Record {
+        return match self {
+            Tree::Leaf(v) => v,
+            Tree::Hash(hash) => Record::from_hash(hash.into_iter().map(|(k, t)| (k, t.into_record())).collect()),
+            Tree::Array(arr) => {
+                let mut vs = Vec::new();
+                for (i, t) in arr {
+                    while vs.len() < i {
+                        vs.push(Record::null());
+                    }
+                    vs.push(t.into_record());
+                }
+                Record::from_vec(vs)
+            }
+        };
+    }
+}
@@ -0,0 +1,253 @@
+use opts::parser::OptionsPile;
+use opts::parser::Optionsable;
+use opts::vals::BooleanOption;
+use opts::vals::OptionalStringOption;
+use opts::vals::OptionalUsizeOption;
+use opts::vals::StringVecOption;
+use record::Record;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+use stream::Entry;
+use stream::Stream;
+use super::OperationBe2;
+use super::OperationBeForBe2;
+use super::OperationRegistrant;
+use validates::Validates;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+#[derive(Clone)]
+enum Mode {
+    Adjacent(Option<String>),
+    Global(Option<String>),
+    Bloom(usize),
+}
+
+#[derive(Default)]
+struct ModeOption {
+    global: BooleanOption,
+    bloom: OptionalUsizeOption,
+    count: OptionalStringOption,
+}
+
+impl Validates for ModeOption {
+    type Target = Mode;
+
+    fn validate(self) -> ValidationResult<Mode> {
+        let global = self.global.validate()?;
+        let count = self.count.validate()?;
+        return Result::Ok(match self.bloom.validate()? {
+            Some(bytes) => {
+                if count.is_some() {
+                    return ValidationError::message("--count cannot be combined with --bloom");
+                }
+                Mode::Bloom(bytes)
+            }
+            None => {
+                if global {
+                    Mode::Global(count)
+                }
+                else {
+                    Mode::Adjacent(count)
+                }
+            }
+        });
+    }
+}
+
+#[derive(Default)]
+#[derive(Validates)]
+pub struct Options {
+    keys: StringVecOption,
+    mode: ModeOption,
+}
+
+pub(crate) type Impl = OperationRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = OperationBeForBe2<ImplBe2>;
+
+pub(crate) struct ImplBe2();
+
+impl Optionsable for ImplBe2 {
+    type Options = Options;
+
+    fn options(opt: &mut OptionsPile<Options>) {
+        opt.match_single(&["k", "key"], |p, a| p.keys.push_split(a), "keys to compare (default: entire record)");
+        opt.match_zero(&["g", "global"], |p| p.mode.global.set(), "drop all repeats, not just adjacent ones (memory grows with distinct keys)");
+        opt.match_zero(&["no-global"], |p| p.mode.global.clear(), "(default)");
+        opt.match_single(&["bloom"], |p, a| p.mode.bloom.parse_size(a), "drop repeats globally using a bloom filter of this many bytes (approximate: some unique records may be dropped)");
+        opt.match_single(&["c", "count"], |p, a| p.mode.count.set_str(a), "key to set to number of records collapsed into each output");
+    }
+}
+
+fn with_count(count: &str, mut r: Record, ct: i64) -> Record {
+    r.set_path(count, Record::from(ct));
+    return r;
+}
+
+impl OperationBe2 for ImplBe2 {
+    fn names() -> Vec<&'static str> {
+        return vec!["uniq", "dedup"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "drop records with repeated values of keys";
+    }
+
+    fn stream(o: Arc<OptionsValidated>) -> Stream {
+        let keys = o.keys.clone();
+        let get_key = move |r: &Record| -> Vec<Record> {
+            if keys.is_empty() {
+                return vec![r.clone()];
+            }
+            return keys.iter().map(|k| r.get_path(k)).collect();
+        };
+
+        match o.mode.clone() {
+            Mode::Adjacent(None) => {
+                return stream::closures(
+                    None,
+                    move |s, e, w| {
+                        let r = e.parse();
+                        let k = get_key(&r);
+
+                        if s.as_ref() == Some(&k) {
+                            return true;
+                        }
+                        *s = Some(k);
+
+                        return w(Entry::Record(r));
+                    },
+                    |_s, _w| {
+                    },
+                );
+            }
+            Mode::Adjacent(Some(count)) => {
+                let count1 = count.clone();
+                return stream::closures(
+                    None,
+                    move |s: &mut Option<(Vec<Record>, Record, i64)>, e, w| {
+                        let r = e.parse();
+                        let k = get_key(&r);
+
+                        if let Some((k0, _r0, ct)) = s {
+                            if *k0 == k {
+                                *ct += 1;
+                                return true;
+                            }
+                        }
+
+                        if let Some((_k0, r0, ct)) = s.replace((k, r, 1)) {
+                            return w(Entry::Record(with_count(&count1, r0, ct)));
+                        }
+
+                        return true;
+                    },
+                    move |s, w| {
+                        if let Some((_k0, r0, ct)) = s {
+                            w(Entry::Record(with_count(&count, r0, ct)));
+                        }
+                    },
+                );
+            }
+            Mode::Global(None) => {
+                return stream::closures(
+                    HashSet::new(),
+                    move |s, e, w| {
+                        let r = e.parse();
+
+                        if !s.insert(get_key(&r)) {
+                            return true;
+                        }
+
+                        return w(Entry::Record(r));
+                    },
+                    |_s, _w| {
+                    },
+                );
+            }
+            Mode::Global(Some(count)) => {
+                return stream::closures(
+                    (HashMap::new(), Vec::new()),
+                    move |s, e, _w| {
+                        let (idxs, rs) = s;
+                        let r = e.parse();
+
+                        let idx = *idxs.entry(get_key(&r)).or_insert_with(|| {
+                            rs.push((r, 0));
+                            return rs.len() - 1;
+                        });
+                        rs[idx].1 += 1;
+
+                        return true;
+                    },
+                    move |s, w| {
+                        for (r, ct) in s.1 {
+                            if !w(Entry::Record(with_count(&count, r, ct))) {
+                                return;
+                            }
+                        }
+                    },
+                );
+            }
+            Mode::Bloom(bytes) => {
+                return stream::closures(
+                    BloomFilter::new(bytes),
+                    move |s, e, w| {
+                        let r = e.parse();
+
+                        if !s.insert(&get_key(&r)) {
+                            return true;
+                        }
+
+                        return w(Entry::Record(r));
+                    },
+                    |_s, _w| {
+                    },
+                );
+            }
+        }
+    }
+}
+
+// Fixed number of probes, which is optimal at roughly 10 bits per distinct
+// key (about a 1% false positive rate).
+const BLOOM_HASHES: u64 = 7;
+
+struct BloomFilter {
+    bits: Vec<u64>,
+}
+
+impl BloomFilter {
+    fn new(bytes: usize) -> Self {
+        return BloomFilter {
+            bits: vec![0; std::cmp::max(1, bytes / 8)],
+        };
+    }
+
+    // Returns true if the value was (probably) not present before.
+    fn insert<T: Hash>(&mut self, t: &T) -> bool {
+        let mut h1 = DefaultHasher::new();
+        t.hash(&mut h1);
+        let h1 = h1.finish();
+        let mut h2 = DefaultHasher::new();
+        (h1, t).hash(&mut h2);
+        let h2 = h2.finish() | 1;
+
+        let nbits = (self.bits.len() as u64) * 64;
+        let mut fresh = false;
+        for i in 0..BLOOM_HASHES {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % nbits;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                fresh = true;
+                self.bits[word] |= mask;
+            }
+        }
+        return fresh;
+    }
+}
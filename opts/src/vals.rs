@@ -194,6 +194,20 @@ impl OptionalUsizeOption {
     pub fn parse(&mut self, a: &str) -> ValidationResult<()> {
         return self.set(a.parse()?);
     }
+
+    pub fn parse_size(&mut self, a: &str) -> ValidationResult<()> {
+        let (n, mult) = match a.chars().last().map(|c| c.to_ascii_uppercase()) {
+            Some('K') => (&a[..(a.len() - 1)], 1 << 10),
+            Some('M') => (&a[..(a.len() - 1)], 1 << 20),
+            Some('G') => (&a[..(a.len() - 1)], 1 << 30),
+            _ => (a, 1),
+        };
+        let n: usize = n.parse()?;
+        return match n.checked_mul(mult) {
+            Some(n) => self.set(n),
+            None => ValidationError::message(format!("Size too large: {}", a)),
+        };
+    }
 }
 
 #[derive(Default)]
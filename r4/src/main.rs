@@ -1,6 +1,10 @@
+extern crate misc;
 extern crate operation;
+extern crate opts;
 extern crate stream;
+extern crate validates;
 
+use opts::parser::OptionsPile;
 use std::env;
 use std::fs::File;
 use std::io::BufRead;
@@ -9,13 +13,37 @@ use std::io::Write;
 use std::io;
 use std::sync::Arc;
 use stream::Entry;
+use validates::ValidationResult;
+
+// Options before the operation name, e.g.  "r4 --seed 5 sort -s shuffle".
+#[derive(Default)]
+struct GlobalOptions {
+    seed: Option<u64>,
+    op: Vec<String>,
+}
+
+fn parse_global_options(args: &[String]) -> ValidationResult<GlobalOptions> {
+    let mut opt = OptionsPile::<GlobalOptions>::new();
+    opt.match_single(&["seed"], |p, a| {
+        p.seed = Some(a.parse()?);
+        return Result::Ok(());
+    }, ("SEED", "seed every random component, for reproducible runs"));
+    opt.match_extra_hard(|p, a| {
+        p.op.extend_from_slice(a);
+        return Result::Ok(());
+    }, "operation to run (and its arguments)");
+    return opt.to_parser().parse(args);
+}
 
 fn main() {
-    let mut args = env::args();
-    args.next().unwrap();
-    let op = args.next().unwrap_or_else(|| "help".to_string());
+    let args: Vec<_> = env::args().skip(1).collect();
+    let globals = parse_global_options(&args).unwrap_or_else(|e| e.panic());
+    if let Some(seed) = globals.seed {
+        misc::seed::set_global_seed(seed);
+    }
+    let mut args = globals.op;
+    let op = if args.is_empty() { "help".to_string() } else { args.remove(0) };
     let op = operation::REGISTRY.find(&op, &[]).unwrap_or_else(|e| e.panic());
-    let mut args = args.collect();
     let op = op.parse(&mut args).unwrap_or_else(|e| e.panic());
 
     let mut w = |e: Entry| {
@@ -44,3 +72,6 @@ fn main() {
 
     os.close(&mut w);
 }
+
+#[cfg(test)]
+mod tests;
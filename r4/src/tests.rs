@@ -0,0 +1,37 @@
+use super::parse_global_options;
+use validates::ValidationError;
+
+fn parse(args: &[&str]) -> Result<(Option<u64>, Vec<String>), String> {
+    let args: Vec<_> = args.iter().map(|s| s.to_string()).collect();
+    return match parse_global_options(&args) {
+        Result::Ok(g) => Result::Ok((g.seed, g.op)),
+        Result::Err(ValidationError::Message(s)) => Result::Err(s),
+        Result::Err(ValidationError::Help(lines)) => Result::Err(lines.join("\n")),
+    };
+}
+
+fn strs(v: &[&str]) -> Vec<String> {
+    return v.iter().map(|s| s.to_string()).collect();
+}
+
+#[test]
+fn test_seed() {
+    assert_eq!(parse(&["sort", "-s", "shuffle"]), Result::Ok((None, strs(&["sort", "-s", "shuffle"]))));
+    assert_eq!(parse(&["--seed", "5", "sort", "-s", "shuffle"]), Result::Ok((Some(5), strs(&["sort", "-s", "shuffle"]))));
+    assert_eq!(parse(&["-seed", "5"]), Result::Ok((Some(5), vec![])));
+    assert_eq!(parse(&["--seed", "1", "--seed", "2", "sort"]), Result::Ok((Some(2), strs(&["sort"]))));
+    // Options after the operation name are the operation's.
+    assert_eq!(parse(&["sort", "--seed", "5"]), Result::Ok((None, strs(&["sort", "--seed", "5"]))));
+}
+
+#[test]
+fn test_bad_seed() {
+    let e = parse(&["--seed", "x", "sort"]).err().unwrap();
+    assert!(e.contains("--seed"), "{}", e);
+    let e = parse(&["--seed", "-1", "sort"]).err().unwrap();
+    assert!(e.contains("--seed"), "{}", e);
+    let e = parse(&["--seed"]).err().unwrap();
+    assert!(e.contains("Not enough arguments for --seed"), "{}", e);
+    let e = parse(&["--sed", "5", "sort"]).err().unwrap();
+    assert!(e.contains("No such option sed"), "{}", e);
+}
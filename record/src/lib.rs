@@ -23,5 +23,15 @@ pub use self::record::Record;
 mod mrecord;
 pub use self::mrecord::MRecord;
 
+mod spill;
+pub use self::spill::SpillFile;
+pub use self::spill::SpillWriter;
+pub use self::spill::approx_size;
+
+mod time;
+pub use self::time::epoch_to_seconds;
+pub use self::time::parse_timestamp;
+pub use self::time::parse_timestamp_lenient;
+
 #[cfg(test)]
 mod tests;
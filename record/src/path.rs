@@ -42,6 +42,11 @@ impl<'a> Path<'a> {
         }).collect());
     }
 
+    // Whether new() would accept this (array steps must be "#" and a number).
+    pub fn is_valid(s: &str) -> bool {
+        return s.is_empty() || s.split('/').all(|e| !e.starts_with('#') || e[1..].parse::<usize>().is_ok());
+    }
+
     pub fn to_owned(self) -> OwnPath {
         return Path(self.0.into_iter().map(|e| {
             return match e {
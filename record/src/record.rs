@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::vec::Vec;
 use super::JsonPrimitive;
 use super::Path;
+use super::RPathStep;
 use super::RecordNode;
 use super::RecordTrait;
 
@@ -30,8 +31,39 @@ impl<T> From<T> for Record where RecordNode<Record>: From<T> {
 }
 
 impl Record {
+    // Paths running through a value of the wrong kind (e.g.  a hash step on
+    // a string) are just absent.
     pub fn has_path(&self, path: &str) -> bool {
-        return self.get_path_opt(path).is_some();
+        return Path::new(path).0.iter().fold(Some(self), |r, part| {
+            return match r {
+                Some(r) => r.0.get_rstep_opt(part),
+                None => None,
+            };
+        }).is_some();
+    }
+
+    // Whether set_path() would succeed, i.e.  every existing value along the
+    // way is null or of the kind its step needs.
+    pub fn can_set_path(&self, path: &str) -> bool {
+        let mut r = self;
+        for part in Path::new(path).0.iter() {
+            if let RecordNode::Primitive(JsonPrimitive::Null()) = *r.0 {
+                return true;
+            }
+            let ok = match (part.as_r(), &*r.0) {
+                (RPathStep::Hash(_), RecordNode::Hash(_)) => true,
+                (RPathStep::Array(_), RecordNode::Array(_)) => true,
+                _ => false,
+            };
+            if !ok {
+                return false;
+            }
+            match r.0.get_rstep_opt(part) {
+                Some(r1) => r = r1,
+                None => return true,
+            }
+        }
+        return true;
     }
 
     pub fn get_path(&self, path: &str) -> Record {
@@ -129,6 +161,44 @@ impl Record {
         return ret;
     }
 
+    // FNV-1a over the JSON (plus a final mix so small shard counts see
+    // well-spread low bits), stable across runs, machines, and builds.  Hash
+    // clumper shards depend on this, so it must never change.
+    pub fn stable_hash(&self) -> u64 {
+        let mut h: u64 = 0xcbf29ce484222325;
+        for b in self.deparse().bytes() {
+            h ^= b as u64;
+            h = h.wrapping_mul(0x100000001b3);
+        }
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51afd7ed558ccd);
+        h ^= h >> 33;
+        return h;
+    }
+
+    // stable_hash() finished off with the rest of the murmur3 mix, for
+    // sketches that need every bit well spread.
+    pub fn stable_hash_mixed(&self) -> u64 {
+        let mut h = self.stable_hash();
+        h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+        h ^= h >> 33;
+        return h;
+    }
+
+    pub fn maybe_array(&self) -> Option<&Vec<Record>> {
+        return match *self.0 {
+            RecordNode::Array(ref arr) => Some(arr),
+            _ => None,
+        };
+    }
+
+    pub fn maybe_hash(&self) -> Option<&BTreeMap<Arc<str>, Record>> {
+        return match *self.0 {
+            RecordNode::Hash(ref hash) => Some(hash),
+            _ => None,
+        };
+    }
+
     pub fn expect_array(&self) -> &Vec<Record> {
         return match *self.0 {
             RecordNode::Array(ref arr) => arr,
@@ -5,6 +5,8 @@ use std::vec::Vec;
 use super::JsonPrimitive;
 use super::PathStep;
 use super::RPathStep;
+use super::time::epoch_to_seconds;
+use super::time::parse_timestamp;
 
 #[derive(Clone)]
 #[derive(Debug)]
@@ -118,6 +120,15 @@ pub trait RecordTrait: std::marker::Sized {
         };
     }
 
+    fn coerce_timestamp(&self) -> f64 {
+        return match self.maybe_primitive() {
+            Some(JsonPrimitive::NumberF64(ref f)) => epoch_to_seconds(f.0),
+            Some(JsonPrimitive::NumberI64(i)) => epoch_to_seconds(i as f64),
+            Some(JsonPrimitive::String(ref s)) => parse_timestamp(s).unwrap_or_else(|| panic!("coerce_timestamp() on unparseable string: {}", s)),
+            _ => panic!("coerce_timestamp() on something incoercible"),
+        };
+    }
+
     fn expect_string(&self) -> Arc<str> {
         return match self.maybe_primitive() {
             Some(JsonPrimitive::String(ref s)) => s.clone(),
@@ -144,6 +155,15 @@ impl<T: RecordTrait> RecordNode<T> {
         }
     }
 
+    // Like get_rstep(), but a step of the wrong kind just finds nothing.
+    pub fn get_rstep_opt(&self, step: &PathStep) -> Option<&T> {
+        return match (step.as_r(), self) {
+            (RPathStep::Hash(s), RecordNode::Hash(hash)) => hash.get(s),
+            (RPathStep::Array(n), RecordNode::Array(arr)) => arr.get(n),
+            _ => None,
+        };
+    }
+
     pub fn get_rstep_mut(&mut self, step: &PathStep) -> Option<&mut T> {
         match step.as_r() {
             RPathStep::Hash(s) => {
@@ -210,8 +230,14 @@ impl<T: RecordTrait> RecordNode<T> {
                 }
                 panic!("delete hash step on non-hash");
             }
-            RPathStep::Array(_n) => {
-                panic!("delete array step");
+            RPathStep::Array(n) => {
+                if let RecordNode::Array(arr) = self {
+                    if n < arr.len() {
+                        return arr.remove(n);
+                    }
+                    return T::null();
+                }
+                panic!("delete array step on non-array");
             }
         }
     }
@@ -0,0 +1,72 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use super::JsonPrimitive;
+use super::Record;
+use super::RecordTrait;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+// A temporary file of records, one per line, removed when dropped.
+pub struct SpillFile(PathBuf);
+
+impl SpillFile {
+    pub fn new() -> (SpillFile, SpillWriter) {
+        let mut path = std::env::temp_dir();
+        path.push(format!("r4-spill-{}-{}", std::process::id(), NEXT_ID.fetch_add(1, Ordering::SeqCst)));
+        let f = OpenOptions::new().write(true).create_new(true).open(&path).unwrap_or_else(|e| panic!("Could not create spill file {}: {}", path.display(), e));
+        return (SpillFile(path.clone()), SpillWriter(BufWriter::new(f), path));
+    }
+
+    pub fn read(&self) -> impl Iterator<Item = Record> {
+        let path = self.0.clone();
+        let f = File::open(&path).unwrap_or_else(|e| panic!("Could not open spill file {}: {}", path.display(), e));
+        return BufReader::new(f).lines().map(move |line| {
+            let line = line.unwrap_or_else(|e| panic!("Could not read spill file {}: {}", path.display(), e));
+            return Record::parse(&line);
+        });
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+pub struct SpillWriter(BufWriter<File>, PathBuf);
+
+impl SpillWriter {
+    pub fn write(&mut self, r: &Record) {
+        if let Err(e) = writeln!(self.0, "{}", r.deparse()) {
+            panic!("Could not write spill file {}: {}", self.1.display(), e);
+        }
+    }
+
+    pub fn finish(mut self) {
+        if let Err(e) = self.0.flush() {
+            panic!("Could not write spill file {}: {}", self.1.display(), e);
+        }
+    }
+}
+
+// Rough estimate of the in-memory footprint of a record, for deciding when to
+// spill.  Shared structure is counted once per reference.
+pub fn approx_size(r: &Record) -> usize {
+    if let Some(arr) = r.maybe_array() {
+        return 16 + arr.iter().map(approx_size).sum::<usize>();
+    }
+    if let Some(hash) = r.maybe_hash() {
+        return 16 + hash.iter().map(|(k, v)| 16 + k.len() + approx_size(v)).sum::<usize>();
+    }
+    return 16 + match r.maybe_primitive() {
+        Some(JsonPrimitive::String(s)) => s.len(),
+        _ => 0,
+    };
+}
@@ -29,3 +29,69 @@ fn test_set_path() {
     r.set_path("a/#2/b", Record::from("c"));
     assert_eq!(r.deparse(), "{\"a\":[null,null,{\"b\":\"c\"}],\"x\":[{\"y\":\"w\"}]}");
 }
+
+#[test]
+fn test_has_path() {
+    let r = Record::parse("{\"x\":[{\"y\":\"z\"}],\"s\":\"t\"}");
+    assert!(r.has_path("x/#0/y"));
+    assert!(!r.has_path("x/#1/y"));
+    assert!(!r.has_path("s/u"));
+    assert!(!r.has_path("x/y"));
+    assert!(r.can_set_path("x/#3/w"));
+    assert!(r.can_set_path("a/b"));
+    assert!(!r.can_set_path("s/u"));
+    assert!(!r.can_set_path("x/#0/y/z"));
+}
+
+#[test]
+fn test_del_path() {
+    let mut r = Record::parse("{\"x\":[1,2,3],\"h\":{\"a\":1}}");
+    assert_eq!(r.del_path("x/#1").deparse(), "2");
+    assert_eq!(r.del_path("x/#5").deparse(), "null");
+    assert_eq!(r.del_path("h/a").deparse(), "1");
+    assert_eq!(r.deparse(), "{\"h\":{},\"x\":[1,3]}");
+    assert!(super::Path::is_valid("a/#0/b"));
+    assert!(!super::Path::is_valid("a/#b"));
+}
+
+#[test]
+fn test_stable_hash() {
+    // Pinned: shard assignments must not move between versions.
+    assert_eq!(Record::from("abc").stable_hash(), 3636307898976039711);
+    assert_eq!(Record::parse("[1,\"x\"]").stable_hash(), 12551055426776391306);
+    assert_ne!(Record::from("abc").stable_hash_mixed(), Record::from("abc").stable_hash());
+}
+
+#[test]
+fn test_parse_timestamp() {
+    use super::parse_timestamp;
+    assert_eq!(parse_timestamp("1500000000"), Some(1500000000.0));
+    assert_eq!(parse_timestamp("1500000000500"), Some(1500000000.5));
+    assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0.0));
+    assert_eq!(parse_timestamp("2017-07-14T02:40:00Z"), Some(1500000000.0));
+    assert_eq!(parse_timestamp("2017-07-14 04:40:00.25+02:00"), Some(1500000000.25));
+    assert_eq!(parse_timestamp("2017-07-14T02:40:00"), None);
+    assert_eq!(parse_timestamp("2017-07-14"), None);
+    assert_eq!(parse_timestamp("[14/Jul/2017:04:40:00 +0200]"), None);
+    assert_eq!(parse_timestamp("Fri, 14 Jul 2017 02:40:00 GMT"), None);
+    assert_eq!(parse_timestamp("yesterday"), None);
+    assert_eq!(parse_timestamp("nan"), None);
+    assert_eq!(parse_timestamp("inf"), None);
+    assert_eq!(parse_timestamp("-Infinity"), None);
+}
+
+#[test]
+fn test_parse_timestamp_lenient() {
+    use super::parse_timestamp_lenient;
+    assert_eq!(parse_timestamp_lenient("1500000000"), Some(1500000000.0));
+    assert_eq!(parse_timestamp_lenient("2017-07-14T02:40:00Z"), Some(1500000000.0));
+    assert_eq!(parse_timestamp_lenient("2017-07-14"), Some(1499990400.0));
+    assert_eq!(parse_timestamp_lenient("[14/Jul/2017:04:40:00 +0200]"), Some(1500000000.0));
+    assert_eq!(parse_timestamp_lenient("14/Jul/2017:04:40:00 +0200"), Some(1500000000.0));
+    assert_eq!(parse_timestamp_lenient("Fri, 14 Jul 2017 02:40:00 GMT"), Some(1500000000.0));
+    assert_eq!(parse_timestamp_lenient("13 Jul 2017 22:40:00 -0400"), Some(1500000000.0));
+    assert_eq!(parse_timestamp_lenient("2017-13-14"), None);
+    assert_eq!(parse_timestamp_lenient("14/Jul/2017:04:40:00"), None);
+    assert_eq!(parse_timestamp_lenient("yesterday"), None);
+    assert_eq!(parse_timestamp_lenient("nan"), None);
+}
@@ -0,0 +1,170 @@
+// Epoch numbers at least this large are taken to be milliseconds (as seconds
+// they would be more than 3000 years out).
+const EPOCH_MILLIS_THRESHOLD: f64 = 1e11;
+
+pub fn epoch_to_seconds(n: f64) -> f64 {
+    if n.abs() >= EPOCH_MILLIS_THRESHOLD {
+        return n / 1000.0;
+    }
+    return n;
+}
+
+// Parses epoch seconds, epoch milliseconds, or RFC 3339 (with 'T' or ' '
+// between date and time), returning epoch seconds.
+pub fn parse_timestamp(s: &str) -> Option<f64> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<f64>() {
+        if !n.is_finite() {
+            return None;
+        }
+        return Some(epoch_to_seconds(n));
+    }
+    return parse_rfc3339(s);
+}
+
+// As parse_timestamp(), but also accepting a bare date, Apache common log
+// format, or RFC 2822.  Only for sorting, where the clumpers stay strict.
+pub fn parse_timestamp_lenient(s: &str) -> Option<f64> {
+    let s = s.trim();
+    return parse_timestamp(s).or_else(|| parse_date(s)).or_else(|| parse_clf(s)).or_else(|| parse_rfc2822(s));
+}
+
+fn parse_date(s: &str) -> Option<f64> {
+    let b = s.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return None;
+    }
+    let year: i64 = s.get(0..4)?.parse().ok()?;
+    let month: i64 = s.get(5..7)?.parse().ok()?;
+    let day: i64 = s.get(8..10)?.parse().ok()?;
+    if month < 1 || month > 12 || day < 1 || day > 31 {
+        return None;
+    }
+    return Some((days_from_civil(year, month, day) * 86400) as f64);
+}
+
+fn parse_month(s: &str) -> Option<i64> {
+    let months = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    return months.iter().position(|m| m.eq_ignore_ascii_case(s)).map(|i| i as i64 + 1);
+}
+
+// "+hhmm" or "-hhmm" (or "GMT"/"UT"/"Z") as seconds east of UTC.
+fn parse_numeric_offset(s: &str) -> Option<i64> {
+    if s == "GMT" || s == "UT" || s == "UTC" || s == "Z" {
+        return Some(0);
+    }
+    let b = s.as_bytes();
+    if b.len() != 5 || (b[0] != b'+' && b[0] != b'-') {
+        return None;
+    }
+    let oh: i64 = s.get(1..3)?.parse().ok()?;
+    let om: i64 = s.get(3..5)?.parse().ok()?;
+    let offset = oh * 3600 + om * 60;
+    return Some(if b[0] == b'-' { -offset } else { offset });
+}
+
+fn parse_hms(s: &str) -> Option<i64> {
+    let parts: Vec<_> = s.split(':').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let hour: i64 = parts[0].parse().ok()?;
+    let minute: i64 = parts[1].parse().ok()?;
+    let second: i64 = parts[2].parse().ok()?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    return Some(hour * 3600 + minute * 60 + second);
+}
+
+fn civil_seconds(year: i64, month: i64, day: i64, hms: i64, offset: i64) -> Option<f64> {
+    if day < 1 || day > 31 {
+        return None;
+    }
+    return Some((days_from_civil(year, month, day) * 86400 + hms - offset) as f64);
+}
+
+// E.g. "10/Oct/2000:13:55:36 -0700", optionally in brackets.
+fn parse_clf(s: &str) -> Option<f64> {
+    let s = s.trim_start_matches('[').trim_end_matches(']');
+    let (dt, offset) = match s.find(' ') {
+        Some(i) => (&s[0..i], &s[(i + 1)..]),
+        None => return None,
+    };
+    let mut parts = dt.splitn(4, |c| c == '/' || c == ':');
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month = parse_month(parts.next()?)?;
+    let year: i64 = parts.next()?.parse().ok()?;
+    return civil_seconds(year, month, day, parse_hms(parts.next()?)?, parse_numeric_offset(offset)?);
+}
+
+// E.g. "Fri, 14 Jul 2017 02:40:00 +0000" (the weekday is optional).
+fn parse_rfc2822(s: &str) -> Option<f64> {
+    let s = match s.find(", ") {
+        Some(i) => &s[(i + 2)..],
+        None => s,
+    };
+    let parts: Vec<_> = s.split_whitespace().collect();
+    if parts.len() != 5 {
+        return None;
+    }
+    let day: i64 = parts[0].parse().ok()?;
+    let month = parse_month(parts[1])?;
+    let year: i64 = parts[2].parse().ok()?;
+    return civil_seconds(year, month, day, parse_hms(parts[3])?, parse_numeric_offset(parts[4])?);
+}
+
+fn parse_rfc3339(s: &str) -> Option<f64> {
+    let b = s.as_bytes();
+    if b.len() < 19 || b[4] != b'-' || b[7] != b'-' || (b[10] != b'T' && b[10] != b't' && b[10] != b' ') || b[13] != b':' || b[16] != b':' {
+        return None;
+    }
+    let year: i64 = s.get(0..4)?.parse().ok()?;
+    let month: i64 = s.get(5..7)?.parse().ok()?;
+    let day: i64 = s.get(8..10)?.parse().ok()?;
+    let hour: i64 = s.get(11..13)?.parse().ok()?;
+    let minute: i64 = s.get(14..16)?.parse().ok()?;
+    let second: i64 = s.get(17..19)?.parse().ok()?;
+    if month < 1 || month > 12 || day < 1 || day > 31 || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &s[19..];
+    let mut frac = 0.0;
+    if rest.starts_with('.') {
+        let digits = rest[1..].find(|c: char| !c.is_ascii_digit()).map(|i| i + 1).unwrap_or(rest.len());
+        if digits == 1 {
+            return None;
+        }
+        frac = format!("0{}", &rest[..digits]).parse().ok()?;
+        rest = &rest[digits..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let rb = rest.as_bytes();
+            if rb.len() != 6 || (rb[0] != b'+' && rb[0] != b'-') || rb[3] != b':' {
+                return None;
+            }
+            let oh: i64 = rest.get(1..3)?.parse().ok()?;
+            let om: i64 = rest.get(4..6)?.parse().ok()?;
+            let offset = oh * 3600 + om * 60;
+            if rb[0] == b'-' { -offset } else { offset }
+        }
+    };
+
+    let secs = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+    return Some(secs as f64 + frac);
+}
+
+// Howard Hinnant's days_from_civil.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    return era * 146097 + doe - 719468;
+}
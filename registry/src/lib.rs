@@ -14,9 +14,32 @@ use std::sync::Arc;
 use validates::ValidationError;
 use validates::ValidationResult;
 
+// Splits "name,arg,..." on commas, except those escaped as "\," (so e.g. code
+// args can contain them).
+pub fn split_args(a: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = a.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&',') => {
+                chars.next();
+                parts.last_mut().unwrap().push(',');
+            }
+            ',' => {
+                parts.push(String::new());
+            }
+            _ => {
+                parts.last_mut().unwrap().push(c);
+            }
+        }
+    }
+    return parts;
+}
+
 struct RegistrantData<R> {
     names: Vec<&'static str>,
     argct: usize,
+    variadic: bool,
     help_meta_suffix: &'static str,
     help_msg: &'static str,
     init: Box<Fn(&[&str]) -> ValidationResult<R> + Send + Sync>,
@@ -41,6 +64,7 @@ impl<R: 'static> Registry<R> {
         let data = Arc::new(RegistrantData {
             names: I::names(),
             argct: I::Args::argct(),
+            variadic: I::Args::variadic(),
             help_meta_suffix: I::Args::help_meta_suffix(),
             help_msg: I::help_msg(),
             init: Box::new(|args| {
@@ -66,13 +90,17 @@ impl<R: 'static> Registry<R> {
     pub fn find(&self, name: &str, args: &[&str]) -> ValidationResult<R> {
         let data = self.find_data(name)?;
 
-        if args.len() != data.argct {
+        if args.len() != data.argct && !(data.variadic && args.len() > data.argct) {
             return ValidationError::message(format!("Wrong number of args for {}", name));
         }
 
         return (data.init)(args);
     }
 
+    pub fn argct(&self, name: &str) -> ValidationResult<usize> {
+        return Result::Ok(self.find_data(name)?.argct);
+    }
+
     pub fn labelled_multiple_options(&'static self, prefixes: &[&str]) -> OptionsPile<Vec<(String, R)>> {
         let mut opt = OptionsPile::<Vec<(String, R)>>::new();
         for (alias, data) in &self.map {
@@ -96,10 +124,9 @@ impl<R: 'static> Registry<R> {
                 Some(i) => (a[0..i].to_string(), &a[(i + 1)..]),
                 None => (a.replace("/", "_"), &a[..]),
             };
-            let mut parts = a.split(',');
-            let name = parts.next().unwrap();
-            let args: Vec<&str> = parts.collect();
-            let r = self.find(name, &args)?;
+            let parts = split_args(a);
+            let args: Vec<_> = parts[1..].iter().map(|s| s as &str).collect();
+            let r = self.find(&parts[0], &args)?;
             rs.push((label, r));
             return Result::Ok(());
         }, help);
@@ -123,10 +150,9 @@ impl<R: 'static> Registry<R> {
     pub fn single_options(&'static self, aliases: &[&str], help: impl ToOptionsHelp) -> OptionsPile<Vec<R>> {
         let mut opt = OptionsPile::<Vec<R>>::new();
         opt.match_single(aliases, move |rs, a| {
-            let mut parts = a.split(',');
-            let name = parts.next().unwrap();
-            let args: Vec<_> = parts.collect();
-            let r = self.find(name, &args)?;
+            let parts = split_args(a);
+            let args: Vec<_> = parts[1..].iter().map(|s| s as &str).collect();
+            let r = self.find(&parts[0], &args)?;
             rs.push(r);
             return Result::Ok(());
         }, help);
@@ -0,0 +1,37 @@
+use super::RegistryArg;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+// Parses durations like "500ms", "30s", "5m", "1.5h", "2d", or "1w" (a bare
+// number is seconds), returning seconds.
+pub fn parse_duration(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let idx = s.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(s.len());
+    let n: f64 = s[..idx].parse().ok()?;
+    let mult = match &s[idx..] {
+        "ms" => 0.001,
+        "" | "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        "d" => 86400.0,
+        "w" => 7.0 * 86400.0,
+        _ => return None,
+    };
+    return Some(n * mult);
+}
+
+// A positive duration argument, in seconds.
+#[derive(Clone)]
+pub struct DurationArg(pub f64);
+
+impl Copy for DurationArg {
+}
+
+impl RegistryArg for DurationArg {
+    fn parse(arg: &str) -> ValidationResult<DurationArg> {
+        return match parse_duration(arg) {
+            Some(d) if d > 0.0 => Result::Ok(DurationArg(d)),
+            _ => ValidationError::message(format!("Invalid duration: {}", arg)),
+        };
+    }
+}
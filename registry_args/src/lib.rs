@@ -1,5 +1,9 @@
 extern crate validates;
 
+mod duration;
+pub use self::duration::DurationArg;
+pub use self::duration::parse_duration;
+
 use std::str::FromStr;
 use std::sync::Arc;
 use validates::ValidationResult;
@@ -17,6 +21,10 @@ impl RegistryArg for Arc<str> {
 pub trait RegistryArgs: Send + Sized + Sync {
     fn help_meta_suffix() -> &'static str;
     fn argct() -> usize;
+    // Whether further args past argct() are accepted (handed to parse() as is).
+    fn variadic() -> bool {
+        return false;
+    }
     fn parse(args: &[&str]) -> ValidationResult<Self>;
 }
 
@@ -32,6 +40,9 @@ impl<T: FromStr + MayRegistryArgFromStr + Send + Sync> RegistryArg for T where T
 impl MayRegistryArgFromStr for usize {
 }
 
+impl MayRegistryArgFromStr for u64 {
+}
+
 //pub type OneIntArgs = OneFromStrArgs<i64>;
 //pub type OneUsizeArgs = OneFromStrArgs<usize>;
 //
@@ -66,3 +77,6 @@ impl MayRegistryArgFromStr for usize {
 //        return Result::Ok((Arc::from(&*args[0]), Arc::from(&*args[1]), Arc::from(&*args[2])));
 //    }
 //}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,21 @@
+use super::DurationArg;
+use super::RegistryArg;
+use super::parse_duration;
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("30"), Some(30.0));
+    assert_eq!(parse_duration("500ms"), Some(0.5));
+    assert_eq!(parse_duration("5m"), Some(300.0));
+    assert_eq!(parse_duration("1.5h"), Some(5400.0));
+    assert_eq!(parse_duration("5x"), None);
+}
+
+#[test]
+fn test_duration_arg() {
+    assert_eq!(DurationArg::parse("1.5h").ok().map(|d| d.0), Some(5400.0));
+    assert_eq!(DurationArg::parse("250ms").ok().map(|d| d.0), Some(0.25));
+    assert_eq!(DurationArg::parse("30").ok().map(|d| d.0), Some(30.0));
+    assert!(DurationArg::parse("0s").is_err());
+    assert!(DurationArg::parse("5y").is_err());
+}
@@ -0,0 +1,84 @@
+use record::Record;
+use std::sync::Arc;
+use super::SortBeFromSimple;
+use super::SortRegistrant;
+use super::SortSimpleBe;
+use super::sort_string;
+
+// Base letters for accented Latin characters.
+fn fold(c: char) -> Option<&'static str> {
+    return match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'Ā' | 'ā' | 'Ă' | 'ă' | 'Ą' | 'ą' => Some("a"),
+        'Æ' | 'æ' => Some("ae"),
+        'Ç' | 'ç' | 'Ć' | 'ć' | 'Ĉ' | 'ĉ' | 'Ċ' | 'ċ' | 'Č' | 'č' => Some("c"),
+        'Ð' | 'ð' | 'Ď' | 'ď' | 'Đ' | 'đ' => Some("d"),
+        'È' | 'É' | 'Ê' | 'Ë' | 'è' | 'é' | 'ê' | 'ë' | 'Ē' | 'ē' | 'Ĕ' | 'ĕ' | 'Ė' | 'ė' | 'Ę' | 'ę' | 'Ě' | 'ě' => Some("e"),
+        'Ĝ' | 'ĝ' | 'Ğ' | 'ğ' | 'Ġ' | 'ġ' | 'Ģ' | 'ģ' => Some("g"),
+        'Ĥ' | 'ĥ' | 'Ħ' | 'ħ' => Some("h"),
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'ì' | 'í' | 'î' | 'ï' | 'Ĩ' | 'ĩ' | 'Ī' | 'ī' | 'Ĭ' | 'ĭ' | 'Į' | 'į' | 'İ' | 'ı' => Some("i"),
+        'Ĳ' | 'ĳ' => Some("ij"),
+        'Ĵ' | 'ĵ' => Some("j"),
+        'Ķ' | 'ķ' | 'ĸ' => Some("k"),
+        'Ĺ' | 'ĺ' | 'Ļ' | 'ļ' | 'Ľ' | 'ľ' | 'Ŀ' | 'ŀ' | 'Ł' | 'ł' => Some("l"),
+        'Ñ' | 'ñ' | 'Ń' | 'ń' | 'Ņ' | 'ņ' | 'Ň' | 'ň' | 'ŉ' | 'Ŋ' | 'ŋ' => Some("n"),
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'Ō' | 'ō' | 'Ŏ' | 'ŏ' | 'Ő' | 'ő' => Some("o"),
+        'Œ' | 'œ' => Some("oe"),
+        'Ŕ' | 'ŕ' | 'Ŗ' | 'ŗ' | 'Ř' | 'ř' => Some("r"),
+        'Ś' | 'ś' | 'Ŝ' | 'ŝ' | 'Ş' | 'ş' | 'Š' | 'š' | 'ſ' => Some("s"),
+        'ß' => Some("ss"),
+        'Ţ' | 'ţ' | 'Ť' | 'ť' | 'Ŧ' | 'ŧ' => Some("t"),
+        'Þ' | 'þ' => Some("th"),
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'ù' | 'ú' | 'û' | 'ü' | 'Ũ' | 'ũ' | 'Ū' | 'ū' | 'Ŭ' | 'ŭ' | 'Ů' | 'ů' | 'Ű' | 'ű' | 'Ų' | 'ų' => Some("u"),
+        'Ŵ' | 'ŵ' => Some("w"),
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ' | 'ŷ' | 'Ÿ' => Some("y"),
+        'Ź' | 'ź' | 'Ż' | 'ż' | 'Ž' | 'ž' => Some("z"),
+        _ => None,
+    };
+}
+
+// Not the Unicode collation algorithm, just accent and case folding: compares
+// lowercased text with Latin accents stripped, then the accented letters,
+// then case (lower first).  Other scripts compare by code point.
+pub fn accent_fold_key(s: &str) -> (String, Vec<u32>, Vec<bool>) {
+    let mut primary = String::new();
+    let mut secondary = Vec::new();
+    let mut tertiary = Vec::new();
+    for c in s.chars() {
+        let lower: String = c.to_lowercase().collect();
+        match fold(c) {
+            Some(base) => {
+                primary.push_str(base);
+                secondary.push(lower.chars().next().unwrap() as u32);
+            }
+            None => {
+                primary.push_str(&lower);
+                secondary.push(0);
+            }
+        }
+        tertiary.push(c.is_uppercase());
+    }
+    return (primary, secondary, tertiary);
+}
+
+pub type Impl = SortRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = SortBeFromSimple<ImplSimpleBe>;
+
+pub struct ImplSimpleBe;
+
+impl SortSimpleBe for ImplSimpleBe {
+    type T = ((String, Vec<u32>, Vec<bool>), Arc<str>);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["accent", "ai"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "sort by a key ignoring case and Latin accents except to break ties (a simple fold, not full Unicode collation)";
+    }
+
+    fn get(r: Record) -> ((String, Vec<u32>, Vec<bool>), Arc<str>) {
+        let s = sort_string(&r);
+        return (accent_fold_key(&s), s);
+    }
+}
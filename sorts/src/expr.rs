@@ -0,0 +1,107 @@
+use executor::CodeArg;
+use executor::LocalStream;
+use record::F64SortDishonorProxy;
+use record::JsonPrimitive;
+use record::Record;
+use record::RecordTrait;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::sync::Arc;
+use super::NullsKey;
+use super::NullsPolicy;
+use super::SortBe;
+use super::SortRegistrant;
+use super::bucket::KeySortBucket;
+use super::bucket::SortBucket;
+use validates::ValidationError;
+use validates::ValidationResult;
+
+// One stream shared by every bucket (rather than one per bucket).
+pub struct Args(Arc<LocalStream>, NullsPolicy);
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return ",code[,nullsfirst|nullslast]";
+    }
+
+    fn argct() -> usize {
+        return 1;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let nulls = match args.len() {
+            1 => NullsPolicy::Default(),
+            2 => match NullsPolicy::parse(args[1]) {
+                Some(nulls) => nulls,
+                None => return ValidationError::message(format!("Invalid nulls placement for expr: {}", args[1])),
+            },
+            _ => return ValidationError::message("Too many arguments for expr"),
+        };
+        let code = CodeArg::parse(args[0]).map_err(|e| e.label("While parsing code"))?;
+        return Result::Ok(Args(Arc::new(code.stream(true)), nulls));
+    }
+}
+
+// Computed values order booleans first, then numbers numerically, then
+// strings lexically, then anything else by its JSON.  Nulls go first unless
+// placed otherwise.
+#[derive(Clone)]
+#[derive(Eq)]
+#[derive(Ord)]
+#[derive(PartialEq)]
+#[derive(PartialOrd)]
+enum ExprKey {
+    Bool(bool),
+    Number(F64SortDishonorProxy),
+    Text(Arc<str>),
+    Other(String),
+}
+
+impl ExprKey {
+    fn of(a: &Args, r: Record) -> NullsKey<ExprKey> {
+        return a.1.key(false, r, |r| {
+            return match r.maybe_primitive() {
+                Some(JsonPrimitive::Bool(b)) => ExprKey::Bool(b),
+                Some(JsonPrimitive::NumberI64(n)) => ExprKey::Number(F64SortDishonorProxy(n as f64)),
+                Some(JsonPrimitive::NumberF64(f)) => ExprKey::Number(F64SortDishonorProxy(f.0)),
+                Some(JsonPrimitive::String(s)) => ExprKey::Text(s),
+                _ => ExprKey::Other(r.deparse()),
+            };
+        });
+    }
+}
+
+pub type Impl = SortRegistrant<ImplBe>;
+
+pub struct ImplBe;
+
+impl SortBe for ImplBe {
+    type Args = Args;
+
+    fn names() -> Vec<&'static str> {
+        return vec!["expr"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "sort by the value of code run on each record (negate it to sort descending)";
+    }
+
+    fn new_bucket(a: &Args, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket> {
+        let a = Args(a.0.clone(), a.1);
+        return KeySortBucket::new(move |r, _i| ExprKey::of(&a, a.0.call(r)), next);
+    }
+
+    fn key(a: &Args, r: &Record) -> Record {
+        return a.0.call(r.clone());
+    }
+
+    fn cmp_keys(a: &Args, k1: &Record, k2: &Record) -> Ordering {
+        return ExprKey::of(a, k1.clone()).cmp(&ExprKey::of(a, k2.clone()));
+    }
+}
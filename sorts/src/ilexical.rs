@@ -0,0 +1,29 @@
+use record::Record;
+use std::sync::Arc;
+use super::SortBeFromSimple;
+use super::SortRegistrant;
+use super::SortSimpleBe;
+use super::sort_string;
+
+pub type Impl = SortRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = SortBeFromSimple<ImplSimpleBe>;
+
+pub struct ImplSimpleBe;
+
+impl SortSimpleBe for ImplSimpleBe {
+    type T = (String, Arc<str>);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["ilexical", "ilex", "il"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "sort by a key, lexically ignoring case";
+    }
+
+    fn get(r: Record) -> (String, Arc<str>) {
+        let s = sort_string(&r);
+        return (s.to_lowercase(), s);
+    }
+}
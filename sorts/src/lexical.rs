@@ -1,9 +1,9 @@
 use record::Record;
-use record::RecordTrait;
 use std::sync::Arc;
 use super::SortBeFromSimple;
 use super::SortRegistrant;
 use super::SortSimpleBe;
+use super::sort_string;
 
 pub type Impl = SortRegistrant<ImplBe>;
 
@@ -23,6 +23,6 @@ impl SortSimpleBe for ImplSimpleBe {
     }
 
     fn get(r: Record) -> Arc<str> {
-        return r.coerce_string();
+        return sort_string(&r);
     }
 }
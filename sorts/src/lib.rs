@@ -1,31 +1,60 @@
+extern crate executor;
 #[macro_use]
 extern crate lazy_static;
+extern crate misc;
 extern crate record;
 extern crate rand;
-extern crate rand_chacha;
 #[macro_use]
 extern crate registry;
 extern crate registry_args;
+extern crate validates;
 
 pub mod bucket;
 use self::bucket::KeySortBucket;
 use self::bucket::SortBucket;
 
+use record::JsonPrimitive;
 use record::Record;
+use record::RecordTrait;
 use registry::Registrant;
-use registry::args::OneKeyRegistryArgs;
 use registry_args::RegistryArgs;
+use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::rc::Rc;
 use std::sync::Arc;
+use validates::ValidationError;
+use validates::ValidationResult;
 
 pub type BoxedSort = Box<SortInbox>;
 
 registry! {
     BoxedSort,
+    accent,
+    expr,
+    ilexical,
     lexical,
+    natural,
     numeric,
     shuffle,
+    time,
+    version,
+}
+
+// Parse a sort spec, either "name,arg1,arg2,..." or, for sorts taking a
+// single arg, "name:arg" (e.g.  "n:-key:nullsfirst").
+pub fn parse(spec: &str) -> ValidationResult<BoxedSort> {
+    if !spec.contains(',') {
+        if let Some(i) = spec.find(':') {
+            let name = &spec[0..i];
+            if REGISTRY.argct(name)? != 1 {
+                return ValidationError::message(format!("Sort {} does not take a single argument, use {},arg,...", name, name));
+            }
+            return REGISTRY.find(name, &[&spec[(i + 1)..]]);
+        }
+    }
+    let parts = registry::split_args(spec);
+    let args: Vec<_> = parts[1..].iter().map(|s| s as &str).collect();
+    return REGISTRY.find(&parts[0], &args);
 }
 
 pub trait SortBe {
@@ -34,10 +63,16 @@ pub trait SortBe {
     fn names() -> Vec<&'static str>;
     fn help_msg() -> &'static str;
     fn new_bucket(a: &Self::Args, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket>;
+    fn key(a: &Self::Args, r: &Record) -> Record;
+    fn cmp_keys(a: &Self::Args, k1: &Record, k2: &Record) -> Ordering;
 }
 
 pub trait SortInbox: Send + Sync {
     fn new_bucket(&self, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket>;
+    // A standalone (serializable) key for a record, for when we can't keep
+    // everything in a bucket at once (e.g.  spilling sorted runs to disk).
+    fn key(&self, r: &Record) -> Record;
+    fn cmp_keys(&self, k1: &Record, k2: &Record) -> Ordering;
     fn box_clone(&self) -> BoxedSort;
 }
 
@@ -56,6 +91,14 @@ impl<B: SortBe + 'static> SortInbox for SortInboxImpl<B> {
         return B::new_bucket(&self.a, next);
     }
 
+    fn key(&self, r: &Record) -> Record {
+        return B::key(&self.a, r);
+    }
+
+    fn cmp_keys(&self, k1: &Record, k2: &Record) -> Ordering {
+        return B::cmp_keys(&self.a, k1, k2);
+    }
+
     fn box_clone(&self) -> BoxedSort {
         return Box::new(SortInboxImpl::<B> {
             a: self.a.clone(),
@@ -85,6 +128,110 @@ impl<B: SortBe + 'static> Registrant<BoxedSort> for SortRegistrant<B> {
     }
 }
 
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum NullsPolicy {
+    // As if null were smaller than everything else, i.e.  first ascending and
+    // last descending (where lexical sorts have always put them, as "").
+    Default(),
+    First(),
+    Last(),
+}
+
+impl NullsPolicy {
+    pub fn parse(s: &str) -> Option<NullsPolicy> {
+        return match s {
+            "nullsfirst" => Some(NullsPolicy::First()),
+            "nullslast" => Some(NullsPolicy::Last()),
+            _ => None,
+        };
+    }
+
+    // Where a null sorts relative to values is decided before any reversal
+    // so nullsfirst/nullslast hold in either direction.
+    pub(crate) fn key<T>(self, descending: bool, r: Record, f: impl Fn(Record) -> T) -> NullsKey<T> {
+        if let Some(JsonPrimitive::Null()) = r.maybe_primitive() {
+            return match self {
+                NullsPolicy::Default() if descending => NullsKey::Last(),
+                NullsPolicy::Default() => NullsKey::First(),
+                NullsPolicy::First() => NullsKey::First(),
+                NullsPolicy::Last() => NullsKey::Last(),
+            };
+        }
+        return NullsKey::Value(f(r));
+    }
+}
+
+#[derive(Clone)]
+#[derive(Eq)]
+#[derive(Ord)]
+#[derive(PartialEq)]
+#[derive(PartialOrd)]
+pub(crate) enum NullsKey<T> {
+    First(),
+    Value(T),
+    Last(),
+}
+
+#[derive(Clone)]
+pub struct SimpleSortArgs {
+    pub key: Arc<str>,
+    pub descending: bool,
+    pub nulls: NullsPolicy,
+}
+
+impl RegistryArgs for SimpleSortArgs {
+    fn help_meta_suffix() -> &'static str {
+        return ",[-]key[:nullsfirst|:nullslast]";
+    }
+
+    fn argct() -> usize {
+        return 1;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<SimpleSortArgs> {
+        return SimpleSortArgs::new(args[0]);
+    }
+}
+
+impl SimpleSortArgs {
+    pub fn new(s: &str) -> ValidationResult<SimpleSortArgs> {
+        let mut key = s;
+        let mut nulls = NullsPolicy::Default();
+        if let Some(i) = key.rfind(':') {
+            if let Some(policy) = NullsPolicy::parse(&key[(i + 1)..]) {
+                key = &key[0..i];
+                nulls = policy;
+            }
+        }
+        let descending = key.starts_with('-');
+        if descending {
+            key = &key[1..];
+        }
+        if key.is_empty() {
+            return ValidationError::message(format!("Empty key in sort argument {}", s));
+        }
+        return Result::Ok(SimpleSortArgs {
+            key: Arc::from(key),
+            descending: descending,
+            nulls: nulls,
+        });
+    }
+
+    fn nulls_key<T>(&self, r: Record, f: impl Fn(Record) -> T) -> NullsKey<T> {
+        return self.nulls.key(self.descending, r, f);
+    }
+}
+
+// The string to sort a value by, deparsing hashes and arrays rather than
+// failing on them.
+pub(crate) fn sort_string(r: &Record) -> Arc<str> {
+    return match r.maybe_primitive() {
+        Some(_) => r.coerce_string(),
+        None => Arc::from(r.deparse()),
+    };
+}
+
 pub trait SortSimpleBe {
     type T: Clone + Ord + 'static;
 
@@ -98,7 +245,7 @@ pub struct SortBeFromSimple<B: SortSimpleBe> {
 }
 
 impl<B: SortSimpleBe> SortBe for SortBeFromSimple<B> {
-    type Args = OneKeyRegistryArgs;
+    type Args = SimpleSortArgs;
 
     fn names() -> Vec<&'static str> {
         return B::names();
@@ -108,11 +255,25 @@ impl<B: SortSimpleBe> SortBe for SortBeFromSimple<B> {
         return B::help_msg();
     }
 
-    fn new_bucket(a: &OneKeyRegistryArgs, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket> {
-        let key = a.key.clone();
-        if key.starts_with('-') {
-            return KeySortBucket::new(move |r, _i| Reverse(B::get(r.get_path(&key[1..]))), next);
+    fn new_bucket(a: &SimpleSortArgs, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket> {
+        let a = a.clone();
+        if a.descending {
+            return KeySortBucket::new(move |r, _i| a.nulls_key(r.get_path(&a.key), |v| Reverse(B::get(v))), next);
         }
-        return KeySortBucket::new(move |r, _i| B::get(r.get_path(&key)), next);
+        return KeySortBucket::new(move |r, _i| a.nulls_key(r.get_path(&a.key), B::get), next);
+    }
+
+    fn key(a: &SimpleSortArgs, r: &Record) -> Record {
+        return r.get_path(&a.key);
+    }
+
+    fn cmp_keys(a: &SimpleSortArgs, k1: &Record, k2: &Record) -> Ordering {
+        if a.descending {
+            return a.nulls_key(k1.clone(), |v| Reverse(B::get(v))).cmp(&a.nulls_key(k2.clone(), |v| Reverse(B::get(v))));
+        }
+        return a.nulls_key(k1.clone(), B::get).cmp(&a.nulls_key(k2.clone(), B::get));
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,60 @@
+use record::Record;
+use std::sync::Arc;
+use super::SortBeFromSimple;
+use super::SortRegistrant;
+use super::SortSimpleBe;
+use super::sort_string;
+
+// Digit runs compare by value (of any length) before text, ties broken by
+// fewer leading zeros.
+#[derive(Clone)]
+#[derive(Eq)]
+#[derive(Ord)]
+#[derive(PartialEq)]
+#[derive(PartialOrd)]
+pub enum NaturalPart {
+    Number(usize, String, usize),
+    Text(String),
+}
+
+pub fn natural_parts(s: &str) -> Vec<NaturalPart> {
+    let mut ret = Vec::new();
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        let digits = c.is_ascii_digit();
+        let end = rest.find(|c: char| c.is_ascii_digit() != digits).unwrap_or(rest.len());
+        let (part, next) = rest.split_at(end);
+        if digits {
+            let trimmed = part.trim_start_matches('0');
+            ret.push(NaturalPart::Number(trimmed.len(), trimmed.to_string(), part.len() - trimmed.len()));
+        }
+        else {
+            ret.push(NaturalPart::Text(part.to_string()));
+        }
+        rest = next;
+    }
+    return ret;
+}
+
+pub type Impl = SortRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = SortBeFromSimple<ImplSimpleBe>;
+
+pub struct ImplSimpleBe;
+
+impl SortSimpleBe for ImplSimpleBe {
+    type T = (Vec<NaturalPart>, Arc<str>);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["natural", "nat"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "sort by a key, lexically but with embedded numbers compared numerically (file2 before file10)";
+    }
+
+    fn get(r: Record) -> (Vec<NaturalPart>, Arc<str>) {
+        let s = sort_string(&r);
+        return (natural_parts(&s), s);
+    }
+}
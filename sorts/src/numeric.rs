@@ -1,6 +1,8 @@
 use record::F64SortDishonorProxy;
+use record::JsonPrimitive;
 use record::Record;
 use record::RecordTrait;
+use std::sync::Arc;
 use super::SortBeFromSimple;
 use super::SortRegistrant;
 use super::SortSimpleBe;
@@ -9,20 +11,41 @@ pub type Impl = SortRegistrant<ImplBe>;
 
 pub(crate) type ImplBe = SortBeFromSimple<ImplSimpleBe>;
 
+// Numbers first, then anything non-numeric lexically (hashes and arrays by
+// their deparse).
+#[derive(Clone)]
+#[derive(Eq)]
+#[derive(Ord)]
+#[derive(PartialEq)]
+#[derive(PartialOrd)]
+pub enum NumericKey {
+    Number(F64SortDishonorProxy),
+    Other(Arc<str>),
+}
+
 pub struct ImplSimpleBe;
 
 impl SortSimpleBe for ImplSimpleBe {
-    type T = F64SortDishonorProxy;
+    type T = NumericKey;
 
     fn names() -> Vec<&'static str> {
         return vec!["numeric", "num", "n"];
     }
 
     fn help_msg() -> &'static str {
-        return "sort by a key, numerically";
+        return "sort by a key, numerically (non-numeric values after numbers, lexically)";
     }
 
-    fn get(r: Record) -> F64SortDishonorProxy {
-        return F64SortDishonorProxy(r.coerce_f64());
+    fn get(r: Record) -> NumericKey {
+        return match r.maybe_primitive() {
+            Some(JsonPrimitive::NumberI64(n)) => NumericKey::Number(F64SortDishonorProxy(n as f64)),
+            Some(JsonPrimitive::NumberF64(f)) => NumericKey::Number(F64SortDishonorProxy(f.0)),
+            Some(JsonPrimitive::String(s)) => match s.parse() {
+                Ok(f) => NumericKey::Number(F64SortDishonorProxy(f)),
+                Err(_) => NumericKey::Other(s),
+            },
+            Some(_) => NumericKey::Other(r.coerce_string()),
+            None => NumericKey::Other(Arc::from(r.deparse())),
+        };
     }
 }
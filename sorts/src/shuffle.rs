@@ -1,5 +1,10 @@
+use misc::seed::SeedSource;
+use misc::seed::SeededRng;
 use rand::Rng;
-use registry::args::ZeroRegistryArgs;
+use record::Record;
+use record::RecordTrait;
+use registry_args::RegistryArg;
+use registry_args::RegistryArgs;
 use std::cmp::Ordering;
 use std::rc::Rc;
 use std::sync::Mutex;
@@ -7,10 +12,42 @@ use super::SortBe;
 use super::SortRegistrant;
 use super::bucket::KeySortBucket;
 use super::bucket::SortBucket;
+use validates::ValidationError;
+use validates::ValidationResult;
 
+pub struct Args {
+    seed: SeedSource,
+}
+
+impl RegistryArgs for Args {
+    fn help_meta_suffix() -> &'static str {
+        return "[,seed]";
+    }
+
+    fn argct() -> usize {
+        return 0;
+    }
+
+    fn variadic() -> bool {
+        return true;
+    }
+
+    fn parse(args: &[&str]) -> ValidationResult<Args> {
+        let seed = match args.len() {
+            0 => None,
+            1 => Some(<u64 as RegistryArg>::parse(args[0]).map_err(|e| e.label("While parsing seed"))?),
+            _ => return ValidationError::message("Too many arguments for shuffle"),
+        };
+        return Result::Ok(Args {
+            seed: SeedSource::new(seed),
+        });
+    }
+}
+
+// Bytes are drawn lazily, only as deep as needed to break ties, from an rng
+// shared by the bucket.
 #[derive(Clone)]
-#[derive(Default)]
-struct RandomSortKey(usize, Rc<Mutex<Vec<u8>>>);
+struct RandomSortKey(usize, Rc<Mutex<Vec<u8>>>, Rc<Mutex<SeededRng>>);
 
 impl PartialEq for RandomSortKey {
     fn eq(&self, other: &Self) -> bool {
@@ -46,35 +83,48 @@ impl Ord for RandomSortKey {
 }
 
 impl RandomSortKey {
-    fn new(i: usize) -> Self {
-        return RandomSortKey(i, Rc::new(Mutex::new(Vec::new())));
+    fn new(i: usize, rng: Rc<Mutex<SeededRng>>) -> Self {
+        return RandomSortKey(i, Rc::new(Mutex::new(Vec::new())), rng);
     }
 
     fn at(&self, i: usize) -> u8 {
         let mut mg = self.1.lock().unwrap();
         while i >= mg.len() {
-            mg.push(rand::thread_rng().gen());
+            mg.push(self.2.lock().unwrap().gen());
         }
         return mg[i];
     }
 }
 
+fn key_num(k: &Record) -> i64 {
+    return k.coerce_num().map_right(|f| f as i64).join();
+}
+
 pub type Impl = SortRegistrant<ImplBe>;
 
 pub struct ImplBe;
 
 impl SortBe for ImplBe {
-    type Args = ZeroRegistryArgs;
+    type Args = Args;
 
     fn names() -> Vec<&'static str> {
         return vec!["shuffle"];
     }
 
     fn help_msg() -> &'static str {
-        return "'sort' randomly";
+        return "'sort' randomly (optionally with a fixed seed)";
+    }
+
+    fn new_bucket(a: &Args, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket> {
+        let rng = Rc::new(Mutex::new(a.seed.rng()));
+        return KeySortBucket::new(move |_r, i| RandomSortKey::new(i, rng.clone()), next);
+    }
+
+    fn key(a: &Args, _r: &Record) -> Record {
+        return Record::from(a.seed.gen::<i64>());
     }
 
-    fn new_bucket(_a: &ZeroRegistryArgs, next: Rc<Fn() -> Box<SortBucket>>) -> Box<SortBucket> {
-        return KeySortBucket::new(|_r, i| RandomSortKey::new(i), next);
+    fn cmp_keys(_a: &Args, k1: &Record, k2: &Record) -> Ordering {
+        return key_num(k1).cmp(&key_num(k2));
     }
 }
@@ -0,0 +1,151 @@
+use record::Record;
+use record::RecordTrait;
+use std::rc::Rc;
+use super::bucket::VecDequeSortBucket;
+use validates::ValidationError;
+
+fn records(vals: &[&str]) -> Vec<Record> {
+    return vals.iter().map(|v| Record::parse(&format!(r#"{{"k":{}}}"#, v))).collect();
+}
+
+// Sorts {"k":v} records by spec in memory, checking the standalone keys
+// (as used when spilling to disk) order the same way, and returns the values.
+fn sorted(spec: &str, vals: &[&str]) -> Vec<String> {
+    let sort = super::parse(spec).unwrap_or_else(|e| e.panic());
+    let mut bucket = sort.new_bucket(Rc::new(VecDequeSortBucket::new));
+    let rs = records(vals);
+    for (i, r) in rs.iter().enumerate() {
+        bucket.add(r.clone(), i);
+    }
+    let mut ret = Vec::new();
+    while let Some((r, _i)) = bucket.remove_first() {
+        ret.push(r.get_path("k").deparse());
+    }
+
+    let mut keyed: Vec<_> = rs.iter().map(|r| (sort.key(r), r.get_path("k").deparse())).collect();
+    keyed.sort_by(|(k1, _), (k2, _)| sort.cmp_keys(k1, k2));
+    let ext: Vec<_> = keyed.into_iter().map(|(_, v)| v).collect();
+    assert_eq!(ext, ret, "cmp_keys disagrees for {}", spec);
+
+    return ret;
+}
+
+fn quoted(vals: &[&str]) -> Vec<String> {
+    return vals.iter().map(|v| format!("\"{}\"", v)).collect();
+}
+
+fn sorted_strs(spec: &str, vals: &[&str]) -> Vec<String> {
+    let q = quoted(vals);
+    let q: Vec<_> = q.iter().map(|s| &s[..]).collect();
+    return sorted(spec, &q);
+}
+
+#[test]
+fn test_ilexical() {
+    assert_eq!(sorted_strs("ilexical,k", &["b", "B", "a", "C"]), quoted(&["a", "B", "b", "C"]));
+    assert_eq!(sorted_strs("il,-k", &["b", "B", "a", "C"]), quoted(&["C", "b", "B", "a"]));
+}
+
+#[test]
+fn test_natural() {
+    assert_eq!(sorted_strs("natural,k", &["x10", "x9", "x010", "y1", "x"]), quoted(&["x", "x9", "x10", "x010", "y1"]));
+    assert_eq!(sorted_strs("nat,k", &["a2b10", "a2b9", "a10b1"]), quoted(&["a2b9", "a2b10", "a10b1"]));
+}
+
+#[test]
+fn test_version() {
+    let vals = ["1.10.0", "1.2.0", "v1.2.0-rc1", "1.2.0+build5", "1.2.0-beta", "2.0"];
+    assert_eq!(sorted_strs("version,k", &vals), quoted(&["1.2.0-beta", "v1.2.0-rc1", "1.2.0", "1.2.0+build5", "1.10.0", "2.0"]));
+    assert_eq!(sorted_strs("ver,-k", &["1.2", "1.10", "1.9"]), quoted(&["1.10", "1.9", "1.2"]));
+}
+
+#[test]
+fn test_accent() {
+    let vals = ["Zoe", "zoé", "zoe", "Zoé", "eclair", "Éclair", "étage", "ß", "ss", "b"];
+    assert_eq!(sorted_strs("accent,k", &vals), quoted(&["b", "eclair", "Éclair", "étage", "ss", "ß", "zoe", "Zoe", "zoé", "Zoé"]));
+    // Only Latin accents are folded, anything else is by code point.
+    assert_eq!(sorted_strs("ai,k", &["б", "а", "a"]), quoted(&["a", "а", "б"]));
+}
+
+#[test]
+fn test_time() {
+    let vals = ["\"2017-07-14T02:40:01Z\"", "1500000000500", "\"Fri, 14 Jul 2017 02:39:00 GMT\"", "\"junk\"", "\"[14/Jul/2017:04:40:02 +0200]\"", "\"2017-07-13\"", "1500000000"];
+    assert_eq!(sorted("time,k", &vals), vec!["\"2017-07-13\"", "\"Fri, 14 Jul 2017 02:39:00 GMT\"", "1500000000", "1500000000500", "\"2017-07-14T02:40:01Z\"", "\"[14/Jul/2017:04:40:02 +0200]\"", "\"junk\""]);
+    assert_eq!(sorted("t,-k", &["1", "\"1970-01-01T00:00:03Z\"", "2"]), vec!["\"1970-01-01T00:00:03Z\"", "2", "1"]);
+}
+
+#[test]
+fn test_expr() {
+    assert_eq!(sorted("expr,-{{k}}", &["3", "10", "-1"]), vec!["10", "3", "-1"]);
+    assert_eq!(sorted("expr,{{k}}", &["\"b\"", "2", "true", "[1]", "\"a\"", "10", "false"]), vec!["false", "true", "2", "10", "\"a\"", "\"b\"", "[1]"]);
+}
+
+#[test]
+fn test_shuffle() {
+    let shuffled = |spec: &str| {
+        let sort = super::parse(spec).unwrap_or_else(|e| e.panic());
+        let mut bucket = sort.new_bucket(Rc::new(VecDequeSortBucket::new));
+        for (i, r) in records(&["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]).into_iter().enumerate() {
+            bucket.add(r, i);
+        }
+        let mut ret = Vec::new();
+        while let Some((_r, i)) = bucket.remove_first() {
+            ret.push(i);
+        }
+        return ret;
+    };
+    let s = shuffled("shuffle,5");
+    assert_eq!(shuffled("shuffle,5"), s);
+    let mut sorted = s.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+    assert!((0..20).any(|seed| shuffled(&format!("shuffle,{}", seed)) != s));
+}
+
+#[test]
+fn test_nulls() {
+    let vals = ["\"b\"", "null", "\"\"", "\"a\""];
+    // By default nulls go first ascending and last descending.
+    assert_eq!(sorted("lexical,k", &vals), vec!["null", "\"\"", "\"a\"", "\"b\""]);
+    assert_eq!(sorted("lexical,-k", &vals), vec!["\"b\"", "\"a\"", "\"\"", "null"]);
+    assert_eq!(sorted("lexical,k:nullsfirst", &vals), vec!["null", "\"\"", "\"a\"", "\"b\""]);
+    assert_eq!(sorted("lexical,-k:nullsfirst", &vals), vec!["null", "\"b\"", "\"a\"", "\"\""]);
+    assert_eq!(sorted("lexical,k:nullslast", &vals), vec!["\"\"", "\"a\"", "\"b\"", "null"]);
+    assert_eq!(sorted("lexical,-k:nullslast", &vals), vec!["\"b\"", "\"a\"", "\"\"", "null"]);
+
+    let vals = ["2", "null", "10"];
+    assert_eq!(sorted("n,k", &vals), vec!["null", "2", "10"]);
+    assert_eq!(sorted("n,-k", &vals), vec!["10", "2", "null"]);
+    assert_eq!(sorted("n:k:nullslast", &vals), vec!["2", "10", "null"]);
+    assert_eq!(sorted("n:-k:nullsfirst", &vals), vec!["null", "10", "2"]);
+
+    let vals = ["\"2017-07-14\"", "null", "1"];
+    assert_eq!(sorted("time,k", &vals), vec!["null", "1", "\"2017-07-14\""]);
+    assert_eq!(sorted("time,k:nullslast", &vals), vec!["1", "\"2017-07-14\"", "null"]);
+
+    let vals = ["\"x\"", "null", "1"];
+    assert_eq!(sorted("expr,{{k}}", &vals), vec!["null", "1", "\"x\""]);
+    assert_eq!(sorted("expr,{{k}},nullslast", &vals), vec!["1", "\"x\"", "null"]);
+    assert!(super::parse("expr,{{k}},nullsmiddle").is_err());
+}
+
+#[test]
+fn test_numeric() {
+    let vals = ["\"10\"", "\"abc\"", "2.5", "\"-1\"", "{\"a\":1}", "\"Abc\"", "true"];
+    assert_eq!(sorted("n,k", &vals), vec!["\"-1\"", "2.5", "\"10\"", "\"Abc\"", "\"abc\"", "true", "{\"a\":1}"]);
+    assert_eq!(sorted("n,-k", &vals), vec!["{\"a\":1}", "true", "\"abc\"", "\"Abc\"", "\"10\"", "2.5", "\"-1\""]);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(sorted("n:-k", &["1", "2"]), vec!["2", "1"]);
+    assert_eq!(sorted(r#"expr,lua:return math.max(r["k"]\,3)"#, &["5", "1", "4", "2"]), vec!["1", "2", "4", "5"]);
+    let e = |spec: &str| match super::parse(spec) {
+        Result::Ok(_) => panic!("parsed {}", spec),
+        Result::Err(ValidationError::Message(s)) => s,
+        Result::Err(ValidationError::Help(_)) => panic!("help for {}", spec),
+    };
+    assert!(e("shuffle:5").contains("does not take a single argument"), "{}", e("shuffle:5"));
+    assert!(e("nope:k").contains("No implementation named nope"), "{}", e("nope:k"));
+    assert!(e("n,:nullsfirst").contains("Empty key"), "{}", e("n,:nullsfirst"));
+}
@@ -0,0 +1,40 @@
+use record::F64SortDishonorProxy;
+use record::JsonPrimitive;
+use record::Record;
+use record::RecordTrait;
+use std::sync::Arc;
+use super::SortBeFromSimple;
+use super::SortRegistrant;
+use super::SortSimpleBe;
+
+pub type Impl = SortRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = SortBeFromSimple<ImplSimpleBe>;
+
+pub struct ImplSimpleBe;
+
+impl SortSimpleBe for ImplSimpleBe {
+    // Unparseable values go after all timestamps, lexically among themselves.
+    type T = (bool, F64SortDishonorProxy, Arc<str>);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["time", "t"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "sort by a key as a timestamp (epoch seconds/millis, RFC 3339, a bare date, RFC 2822, or common log format)";
+    }
+
+    fn get(r: Record) -> (bool, F64SortDishonorProxy, Arc<str>) {
+        let t = match r.maybe_primitive() {
+            Some(JsonPrimitive::NumberI64(n)) => Some(record::epoch_to_seconds(n as f64)),
+            Some(JsonPrimitive::NumberF64(f)) => Some(record::epoch_to_seconds(f.0)),
+            Some(JsonPrimitive::String(s)) => record::parse_timestamp_lenient(&s),
+            _ => None,
+        };
+        return match t {
+            Some(t) => (false, F64SortDishonorProxy(t), Arc::from("")),
+            None => (true, F64SortDishonorProxy(0.0), Arc::from(r.deparse())),
+        };
+    }
+}
@@ -0,0 +1,48 @@
+use record::Record;
+use std::sync::Arc;
+use super::SortBeFromSimple;
+use super::SortRegistrant;
+use super::SortSimpleBe;
+use super::natural::NaturalPart;
+use super::natural::natural_parts;
+use super::sort_string;
+
+// Dot separated components compared naturally, with a pre-release (after
+// "-") sorting before the release itself and build metadata (after "+")
+// ignored, as in semver.
+pub fn version_key(s: &str) -> (Vec<Vec<NaturalPart>>, bool, Vec<Vec<NaturalPart>>) {
+    let s = s.trim_start_matches(|c| c == 'v' || c == 'V');
+    let s = match s.find('+') {
+        Some(i) => &s[0..i],
+        None => s,
+    };
+    let (core, pre) = match s.find('-') {
+        Some(i) => (&s[0..i], Some(&s[(i + 1)..])),
+        None => (s, None),
+    };
+    let parts = |s: &str| s.split('.').map(natural_parts).collect();
+    return (parts(core), pre.is_none(), pre.map(parts).unwrap_or_else(Vec::new));
+}
+
+pub type Impl = SortRegistrant<ImplBe>;
+
+pub(crate) type ImplBe = SortBeFromSimple<ImplSimpleBe>;
+
+pub struct ImplSimpleBe;
+
+impl SortSimpleBe for ImplSimpleBe {
+    type T = ((Vec<Vec<NaturalPart>>, bool, Vec<Vec<NaturalPart>>), Arc<str>);
+
+    fn names() -> Vec<&'static str> {
+        return vec!["version", "ver"];
+    }
+
+    fn help_msg() -> &'static str {
+        return "sort by a key as a version number (1.9.0 before 1.10.0, 1.0.0-rc1 before 1.0.0)";
+    }
+
+    fn get(r: Record) -> ((Vec<Vec<NaturalPart>>, bool, Vec<Vec<NaturalPart>>), Arc<str>) {
+        let s = sort_string(&r);
+        return (version_key(&s), s);
+    }
+}